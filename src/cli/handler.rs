@@ -1 +1,276 @@
-pub async fn run() {}
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+
+use crate::{
+    config::DEFAULT_DATABASE_PATH,
+    database::SqliteDevilRepository,
+    models::{Dataset, LinkTargets, ScrapeReport},
+    scraper::{
+        CacheMode, CharacterScraper, DevilScraper, DevilScraperConfig, HttpFetcher,
+        HttpFetcherConfig, MediaWikiScraper, ScraperSelectors, CHAINSAWMAN_WIKI_API_URL,
+        CHAINSAWMAN_WIKI_BASE_URL, DEFAULT_CACHE_DIR, DEFAULT_USER_AGENT, HTTP_MAX_RETRIES,
+        HTTP_MAX_RETRY_AFTER_MS, HTTP_TIMEOUT_MS, NUM_OF_SCRAPER_WORKERS, RATE_LIMIT_BURST,
+        REQUESTS_PER_SECOND,
+    },
+    services::{CharacterDataSource, CharacterService, DevilDataSource, DevilService},
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DataSource {
+    /// Rendered wiki pages
+    Html,
+    /// Wikitext served by the MediaWiki Action API
+    #[value(name = "mediawiki")]
+    MediaWiki,
+}
+
+#[derive(Debug, Args)]
+pub struct ScraperArgs {
+    /// File the dataset of scraped devils is written to as JSON
+    #[arg(short, long, default_value = "devils.json")]
+    pub output: PathBuf,
+
+    /// File the dataset of scraped characters is written to as JSON
+    #[arg(long, default_value = "characters.json")]
+    pub characters_output: PathBuf,
+
+    /// File the scrape report is written to as JSON
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+
+    /// SQLite database the scraped devils are stored in
+    #[arg(long, default_value = DEFAULT_DATABASE_PATH)]
+    pub database: PathBuf,
+
+    /// Where the devils are scraped from
+    #[arg(short, long, value_enum, default_value_t = DataSource::Html)]
+    pub source: DataSource,
+
+    /// Number of devil pages scraped concurrently
+    #[arg(short, long, default_value_t = NUM_OF_SCRAPER_WORKERS)]
+    pub workers: usize,
+
+    /// Number of requests per second made to the wiki, shared by every worker
+    #[arg(short, long, default_value_t = REQUESTS_PER_SECOND)]
+    pub rate: f64,
+
+    /// Deprecated, the delay in milliseconds between two requests, it sets the rate to
+    /// `1000 / delay_ms` requests per second
+    #[arg(short, long, hide = true, conflicts_with = "rate")]
+    pub delay_ms: Option<u64>,
+
+    /// Number of requests made at once before being throttled to the rate
+    #[arg(short, long, default_value_t = RATE_LIMIT_BURST)]
+    pub burst: u32,
+
+    /// Timeout in milliseconds of a single request to the wiki
+    #[arg(long, default_value_t = HTTP_TIMEOUT_MS)]
+    pub timeout_ms: u64,
+
+    /// Number of times a failed request to the wiki is retried
+    #[arg(long, default_value_t = HTTP_MAX_RETRIES)]
+    pub retries: u32,
+
+    /// Longest wait in milliseconds asked by a `Retry-After` header that is honored, a request
+    /// asked to wait longer fails
+    #[arg(long, default_value_t = HTTP_MAX_RETRY_AFTER_MS)]
+    pub max_retry_after_ms: u64,
+
+    /// User agent sent to the wiki, it should identify the scraper and how to contact its operator
+    #[arg(long, default_value = DEFAULT_USER_AGENT)]
+    pub user_agent: String,
+
+    /// Directory responses of the wiki are cached in
+    #[arg(long, default_value = DEFAULT_CACHE_DIR)]
+    pub cache_dir: PathBuf,
+
+    /// Refetch every page instead of revalidating the cached responses
+    #[arg(long, conflicts_with = "offline")]
+    pub refresh: bool,
+
+    /// Scrape from the cached responses only, without making any request to the wiki
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Scrape every devil again instead of only the devils whose page changed since the last scrape
+    #[arg(long)]
+    pub full: bool,
+
+    /// TOML mapping of the selectors of the rendered wiki pages, the built-in mapping is used
+    /// when it isn't given
+    #[arg(long)]
+    pub selectors: Option<PathBuf>,
+}
+
+pub async fn run(args: &ScraperArgs) {
+    if args.workers == 0 {
+        fatal!("The number of workers must be greater than 0");
+    }
+    let rate = match args.delay_ms {
+        Some(0) => fatal!("The delay must be greater than 0"),
+        Some(delay_ms) => {
+            tracing::warn!("--delay-ms is deprecated, use --rate instead");
+            1000.0 / delay_ms as f64
+        }
+        None => args.rate,
+    };
+    if rate <= 0.0 || !rate.is_finite() {
+        fatal!("The rate must be greater than 0");
+    }
+    if args.burst == 0 {
+        fatal!("The burst must be greater than 0");
+    }
+
+    let selectors = match &args.selectors {
+        Some(path) => Arc::new(
+            ScraperSelectors::from_file(path)
+                .unwrap_or_else(|err| fatal!("Unable to load {}: {}", path.display(), err)),
+        ),
+        None => ScraperSelectors::builtin(),
+    };
+
+    let config = DevilScraperConfig {
+        num_of_workers: args.workers,
+        selectors,
+    };
+    let fetcher = Arc::new(HttpFetcher::new(HttpFetcherConfig {
+        timeout: Duration::from_millis(args.timeout_ms),
+        max_retries: args.retries,
+        max_retry_after: Duration::from_millis(args.max_retry_after_ms),
+        requests_per_second: rate,
+        burst: args.burst,
+        user_agent: args.user_agent.clone(),
+        cache_dir: Some(args.cache_dir.clone()),
+        cache_mode: if args.offline {
+            CacheMode::Offline
+        } else if args.refresh {
+            CacheMode::Refresh
+        } else {
+            CacheMode::Revalidate
+        },
+        ..HttpFetcherConfig::default()
+    }));
+    let (scraper, character_scraper): (Arc<dyn DevilDataSource>, Arc<dyn CharacterDataSource>) =
+        match args.source {
+            DataSource::Html => (
+                Arc::new(DevilScraper::new(config.clone(), fetcher.clone())),
+                Arc::new(CharacterScraper::new(config, fetcher)),
+            ),
+            DataSource::MediaWiki => {
+                let scraper = Arc::new(MediaWikiScraper::new(config, fetcher));
+                (scraper.clone(), scraper)
+            }
+        };
+    let repository = SqliteDevilRepository::open(&args.database)
+        .unwrap_or_else(|err| fatal!("Unable to open {}: {}", args.database.display(), err));
+    let repository = Arc::new(repository);
+    let service = DevilService::new(scraper, repository.clone());
+    let character_service = CharacterService::new(character_scraper, repository);
+
+    tracing::info!(
+        "Scraping devils with {} workers at {} requests per second",
+        args.workers,
+        rate
+    );
+
+    let mut result = service
+        .scrape(args.full)
+        .await
+        .unwrap_or_else(|err| fatal!("Failed to scrape devils: {}", err));
+
+    print_report(&result.report);
+
+    let mut characters = character_service
+        .scrape(args.full)
+        .await
+        .unwrap_or_else(|err| fatal!("Failed to scrape characters: {}", err));
+
+    // Links between devils and characters are resolved once both of them are scraped, the
+    // same way the repository resolves them when the details are read
+    let mut targets = LinkTargets::default();
+    for detail in &result.details {
+        targets.insert(&detail.wiki_url, &detail.id);
+    }
+    for detail in &characters.details {
+        targets.insert(&detail.wiki_url, &detail.id);
+    }
+    for detail in result.details.iter_mut() {
+        targets.resolve(&mut detail.contracts);
+        targets.resolve(&mut detail.relatives);
+    }
+    for detail in characters.details.iter_mut() {
+        targets.resolve(&mut detail.contracts);
+        targets.resolve(&mut detail.relatives);
+    }
+
+    let source_url = match args.source {
+        DataSource::Html => CHAINSAWMAN_WIKI_BASE_URL,
+        DataSource::MediaWiki => CHAINSAWMAN_WIKI_API_URL,
+    };
+    let dataset = Dataset::new(result.report.started_at, source_url, result.details);
+    write_json(&args.output, &dataset);
+    if let Some(path) = &args.report {
+        write_json(path, &result.report);
+    }
+
+    tracing::info!(
+        "Scraped {} devils into {} and {}",
+        dataset.entries.len(),
+        args.output.display(),
+        args.database.display()
+    );
+
+    let characters_dataset =
+        Dataset::new(characters.report.started_at, source_url, characters.details);
+    write_json(&args.characters_output, &characters_dataset);
+
+    tracing::info!(
+        "Scraped {} characters into {} and {}",
+        characters_dataset.entries.len(),
+        args.characters_output.display(),
+        args.database.display()
+    );
+
+    print_report(&characters.report);
+    if !result.report.failed.is_empty() {
+        fatal!("Failed to scrape {} devils", result.report.failed.len());
+    }
+    if !characters.report.failed.is_empty() {
+        fatal!(
+            "Failed to scrape {} characters",
+            characters.report.failed.len()
+        );
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) {
+    let file = File::create(path)
+        .unwrap_or_else(|err| fatal!("Unable to create {}: {}", path.display(), err));
+    serde_json::to_writer_pretty(BufWriter::new(file), value)
+        .unwrap_or_else(|err| fatal!("Unable to write {}: {}", path.display(), err));
+}
+
+fn print_report(report: &ScrapeReport) {
+    println!(
+        "Scrape finished in {}ms: {} succeeded, {} failed, {} unchanged",
+        report.duration_ms(),
+        report.succeeded.len(),
+        report.failed.len(),
+        report.unchanged.len()
+    );
+
+    for failed in &report.failed {
+        println!(
+            "  [{}] {} ({}): {}",
+            failed.error_kind, failed.wiki_url, failed.category, failed.error
+        );
+    }
+}
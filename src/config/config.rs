@@ -3,6 +3,8 @@ use std::env;
 
 use super::ConfigError;
 
+pub const DEFAULT_DATABASE_PATH: &str = "chainsawman.db";
+
 #[derive(Debug)]
 pub enum ConfigSource {
     Os,
@@ -22,6 +24,11 @@ impl fmt::Display for ConfigSource {
 pub struct Config {
     pub source: ConfigSource,
     pub port: u16,
+    pub database_path: String,
+    /// Dataset of devils written by the scraper, loaded into the database at startup
+    pub devils_dataset_path: Option<String>,
+    /// Dataset of characters written by the scraper, loaded into the database at startup
+    pub characters_dataset_path: Option<String>,
 }
 
 impl Config {
@@ -41,6 +48,10 @@ impl Config {
         Ok(Config {
             source: ConfigSource::Os,
             port: env::var("PORT")?.parse()?,
+            database_path: env::var("DATABASE_PATH")
+                .unwrap_or_else(|_| DEFAULT_DATABASE_PATH.to_string()),
+            devils_dataset_path: env::var("DEVILS_DATASET_PATH").ok(),
+            characters_dataset_path: env::var("CHARACTERS_DATASET_PATH").ok(),
         })
     }
 
@@ -48,6 +59,10 @@ impl Config {
         Ok(Config {
             source: ConfigSource::Dotenv,
             port: dotenvy::var("PORT")?.parse()?,
+            database_path: dotenvy::var("DATABASE_PATH")
+                .unwrap_or_else(|_| DEFAULT_DATABASE_PATH.to_string()),
+            devils_dataset_path: dotenvy::var("DEVILS_DATASET_PATH").ok(),
+            characters_dataset_path: dotenvy::var("CHARACTERS_DATASET_PATH").ok(),
         })
     }
 }
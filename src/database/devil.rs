@@ -0,0 +1,935 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Error,
+    path::Path,
+    sync::{Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use duplicate::duplicate_item;
+use rusqlite::{
+    params,
+    types::{FromSql, FromSqlResult, ToSqlOutput, Type, ValueRef},
+    Connection, OptionalExtension, ToSql, Transaction,
+};
+
+use crate::{
+    models::{
+        Ability, AbilitySection, Character, CharacterDetail, Devil, DevilCategory, DevilDetail,
+        DevilImage, DevilName, EntityLink, EntityLinks, Gender, LinkTargets, ParseWarning,
+        Revision, ScrapeReport, Status,
+    },
+    services::{CharacterRepository, DevilRepository},
+};
+
+const SCHEMA: &str = r#"
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE IF NOT EXISTS devils (
+    id TEXT PRIMARY KEY,
+    devil_name TEXT NOT NULL,
+    alias_name TEXT,
+    wiki_url TEXT NOT NULL,
+    category TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS characters (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    alias_name TEXT,
+    wiki_url TEXT NOT NULL,
+    category TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS scrape_reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL DEFAULT 'devils',
+    started_at TEXT NOT NULL,
+    report TEXT NOT NULL
+);
+"#;
+
+/// Tables of the details of an entity, created for the devils and the characters. `{entity}` is
+/// replaced by the name of the entity and `{entities}` by the table of its listed pages
+const DETAIL_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS {entity}_details (
+    {entity}_id TEXT PRIMARY KEY REFERENCES {entities} (id) ON DELETE CASCADE,
+    image_src TEXT,
+    gender TEXT,
+    birthplace TEXT,
+    status TEXT,
+    revision_id INTEGER,
+    revision_timestamp TEXT
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_names (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    language TEXT NOT NULL,
+    {entity}_name TEXT NOT NULL,
+    alias_name TEXT,
+    reading TEXT,
+    alias_reading TEXT,
+    PRIMARY KEY ({entity}_id, language)
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_images (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    src TEXT NOT NULL,
+    label TEXT,
+    caption TEXT,
+    PRIMARY KEY ({entity}_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_occupations (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY ({entity}_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_affiliations (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY ({entity}_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_contracts (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    wiki_url TEXT,
+    qualifier TEXT,
+    PRIMARY KEY ({entity}_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_relatives (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    wiki_url TEXT,
+    qualifier TEXT,
+    PRIMARY KEY ({entity}_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_abilities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    parent_id INTEGER REFERENCES {entity}_abilities (id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_ability_sections (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    key TEXT NOT NULL,
+    title TEXT NOT NULL,
+    PRIMARY KEY ({entity}_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS {entity}_warnings (
+    {entity}_id TEXT NOT NULL REFERENCES {entities} (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    field TEXT NOT NULL,
+    message TEXT NOT NULL,
+    PRIMARY KEY ({entity}_id, position)
+);
+"#;
+
+/// Columns added to a table after it was first created, as `(table, column, definition)`,
+/// they are added to the databases created before them
+const ADDED_COLUMNS: [(&str, &str, &str); 9] = [
+    ("devil_details", "revision_id", "INTEGER"),
+    ("devil_details", "revision_timestamp", "TEXT"),
+    ("devil_contracts", "wiki_url", "TEXT"),
+    ("devil_contracts", "qualifier", "TEXT"),
+    ("devil_relatives", "wiki_url", "TEXT"),
+    ("devil_relatives", "qualifier", "TEXT"),
+    ("devil_names", "reading", "TEXT"),
+    ("devil_names", "alias_reading", "TEXT"),
+    ("scrape_reports", "entity", "TEXT NOT NULL DEFAULT 'devils'"),
+];
+
+/// Entity whose details are stored in the tables of `DETAIL_SCHEMA`
+#[derive(Clone, Copy)]
+struct Entity {
+    name: &'static str,
+    table: &'static str,
+}
+
+const DEVIL: Entity = Entity {
+    name: "devil",
+    table: "devils",
+};
+const CHARACTER: Entity = Entity {
+    name: "character",
+    table: "characters",
+};
+
+impl Entity {
+    /// Statement on the tables of the entity, written with the placeholders of `DETAIL_SCHEMA`
+    fn sql(&self, sql: &str) -> String {
+        sql.replace("{entities}", self.table)
+            .replace("{entity}", self.name)
+    }
+}
+
+pub struct SqliteDevilRepository {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteDevilRepository {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut conn = Connection::open(path).map_err(Error::other)?;
+        conn.execute_batch(SCHEMA).map_err(Error::other)?;
+        migrate_character_details(&mut conn).map_err(Error::other)?;
+        for entity in [DEVIL, CHARACTER] {
+            conn.execute_batch(&entity.sql(DETAIL_SCHEMA))
+                .map_err(Error::other)?;
+        }
+        add_missing_columns(&conn).map_err(Error::other)?;
+
+        Ok(SqliteDevilRepository {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        // A poisoned connection is still usable since every write happens in a transaction
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl DevilRepository for SqliteDevilRepository {
+    async fn save(&self, devils: &[Devil], details: &[DevilDetail]) -> Result<(), Error> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(Error::other)?;
+
+        tx.execute("DELETE FROM devils", []).map_err(Error::other)?;
+
+        // A page listed in several category galleries is stored once, as its first listing
+        let mut ids: HashSet<&str> = HashSet::new();
+        for devil in devils.iter().filter(|devil| ids.insert(&devil.id)) {
+            tx.execute(
+                "INSERT INTO devils (id, devil_name, alias_name, wiki_url, category) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![devil.id, devil.devil_name, devil.alias_name, devil.wiki_url, devil.category],
+            )
+            .map_err(Error::other)?;
+        }
+
+        let mut ids: HashSet<&str> = HashSet::new();
+        for detail in details.iter().filter(|detail| ids.insert(&detail.id)) {
+            insert_devil_detail(&tx, detail).map_err(Error::other)?;
+        }
+
+        tx.commit().map_err(Error::other)
+    }
+
+    async fn find_devils(&self) -> Result<Vec<Devil>, Error> {
+        let conn = self.conn();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, devil_name, alias_name, wiki_url, category FROM devils ORDER BY id",
+            )
+            .map_err(Error::other)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(Devil {
+                    id: row.get(0)?,
+                    devil_name: row.get(1)?,
+                    alias_name: row.get(2)?,
+                    wiki_url: row.get(3)?,
+                    category: row.get(4)?,
+                })
+            })
+            .map_err(Error::other)?;
+
+        rows.collect::<Result<Vec<Devil>, _>>()
+            .map_err(Error::other)
+    }
+
+    async fn find_devil_detail(&self, id: &str) -> Result<Option<DevilDetail>, Error> {
+        let conn = self.conn();
+        select_devil_detail(&conn, id).map_err(Error::other)
+    }
+
+    async fn find_devil_links(&self) -> Result<HashMap<String, EntityLinks>, Error> {
+        let conn = self.conn();
+        select_all_links(&conn, DEVIL).map_err(Error::other)
+    }
+
+    async fn find_revisions(&self) -> Result<HashMap<String, Revision>, Error> {
+        let conn = self.conn();
+        select_revisions(&conn, DEVIL).map_err(Error::other)
+    }
+
+    async fn save_report(&self, report: &ScrapeReport) -> Result<(), Error> {
+        insert_report(&self.conn(), DEVIL, report)
+    }
+
+    async fn find_latest_report(&self) -> Result<Option<ScrapeReport>, Error> {
+        select_latest_report(&self.conn(), DEVIL)
+    }
+}
+
+#[async_trait]
+impl CharacterRepository for SqliteDevilRepository {
+    async fn save_characters(
+        &self,
+        characters: &[Character],
+        details: &[CharacterDetail],
+    ) -> Result<(), Error> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(Error::other)?;
+
+        tx.execute("DELETE FROM characters", [])
+            .map_err(Error::other)?;
+
+        let mut ids: HashSet<&str> = HashSet::new();
+        for character in characters
+            .iter()
+            .filter(|character| ids.insert(&character.id))
+        {
+            tx.execute(
+                "INSERT INTO characters (id, name, alias_name, wiki_url, category) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![character.id, character.name, character.alias_name, character.wiki_url, character.category],
+            )
+            .map_err(Error::other)?;
+        }
+
+        let mut ids: HashSet<&str> = HashSet::new();
+        for detail in details.iter().filter(|detail| ids.insert(&detail.id)) {
+            insert_character_detail(&tx, detail).map_err(Error::other)?;
+        }
+
+        tx.commit().map_err(Error::other)
+    }
+
+    async fn find_characters(&self) -> Result<Vec<Character>, Error> {
+        let conn = self.conn();
+
+        let mut stmt = conn
+            .prepare("SELECT id, name, alias_name, wiki_url, category FROM characters ORDER BY id")
+            .map_err(Error::other)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(Character {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    alias_name: row.get(2)?,
+                    wiki_url: row.get(3)?,
+                    category: row.get(4)?,
+                })
+            })
+            .map_err(Error::other)?;
+
+        rows.collect::<Result<Vec<Character>, _>>()
+            .map_err(Error::other)
+    }
+
+    async fn find_character_detail(&self, id: &str) -> Result<Option<CharacterDetail>, Error> {
+        let conn = self.conn();
+        select_character_detail(&conn, id).map_err(Error::other)
+    }
+
+    async fn find_character_links(&self) -> Result<HashMap<String, EntityLinks>, Error> {
+        let conn = self.conn();
+        select_all_links(&conn, CHARACTER).map_err(Error::other)
+    }
+
+    async fn find_character_revisions(&self) -> Result<HashMap<String, Revision>, Error> {
+        let conn = self.conn();
+        select_revisions(&conn, CHARACTER).map_err(Error::other)
+    }
+
+    async fn save_character_report(&self, report: &ScrapeReport) -> Result<(), Error> {
+        insert_report(&self.conn(), CHARACTER, report)
+    }
+
+    async fn find_latest_character_report(&self) -> Result<Option<ScrapeReport>, Error> {
+        select_latest_report(&self.conn(), CHARACTER)
+    }
+}
+
+// Kinds are stored as the text they are serialized to, the text stored before they had a kind
+// is normalized when read
+#[duplicate_item(Kind; [DevilCategory]; [Status]; [Gender])]
+impl ToSql for Kind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+#[duplicate_item(Kind; [DevilCategory]; [Status]; [Gender])]
+impl FromSql for Kind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str().map(Kind::parse)
+    }
+}
+
+fn add_missing_columns(conn: &Connection) -> rusqlite::Result<()> {
+    for (table, column, definition) in ADDED_COLUMNS {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+            params![table, column],
+            |row| row.get(0),
+        )?;
+
+        if !exists {
+            conn.execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, definition
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The details of the characters were first stored as JSON in a single `detail` column, they
+/// are moved to the tables of `DETAIL_SCHEMA` the details of the devils are stored in
+fn migrate_character_details(conn: &mut Connection) -> rusqlite::Result<()> {
+    let is_json: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('character_details') WHERE name = 'detail'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !is_json {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+    let details = {
+        let mut stmt = tx.prepare("SELECT detail FROM character_details")?;
+        let rows = stmt.query_map([], |row| {
+            let json: String = row.get(0)?;
+            serde_json::from_str::<CharacterDetail>(&json).map_err(|err| {
+                rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(err))
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<CharacterDetail>>>()?
+    };
+
+    tx.execute_batch("DROP TABLE character_details")?;
+    tx.execute_batch(&CHARACTER.sql(DETAIL_SCHEMA))?;
+    for detail in &details {
+        insert_character_detail(&tx, detail)?;
+    }
+
+    tx.commit()
+}
+
+#[duplicate_item(
+    insert_detail               detail_type         entity;
+    [insert_devil_detail]       [DevilDetail]       [DEVIL];
+    [insert_character_detail]   [CharacterDetail]   [CHARACTER];
+)]
+fn insert_detail(tx: &Transaction, detail: &detail_type) -> rusqlite::Result<()> {
+    tx.execute(
+        &entity.sql("INSERT INTO {entity}_details ({entity}_id, image_src, gender, birthplace, status, revision_id, revision_timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"),
+        params![
+            detail.id,
+            detail.image_src,
+            detail.gender,
+            detail.birthplace,
+            detail.status,
+            detail.revision_id,
+            detail.revision_timestamp
+        ],
+    )?;
+
+    for (language, name) in &detail.names {
+        tx.execute(
+            &entity.sql("INSERT INTO {entity}_names ({entity}_id, language, {entity}_name, alias_name, reading, alias_reading) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"),
+            params![
+                detail.id,
+                language,
+                name.devil_name,
+                name.alias_name,
+                name.reading,
+                name.alias_reading
+            ],
+        )?;
+    }
+
+    for (position, image) in detail.images.iter().enumerate() {
+        tx.execute(
+            &entity.sql("INSERT INTO {entity}_images ({entity}_id, position, src, label, caption) VALUES (?1, ?2, ?3, ?4, ?5)"),
+            params![detail.id, position, image.src, image.label, image.caption],
+        )?;
+    }
+
+    insert_values(tx, entity, "occupations", &detail.id, &detail.occupations)?;
+    insert_values(tx, entity, "affiliations", &detail.id, &detail.affiliations)?;
+    insert_links(tx, entity, "contracts", &detail.id, &detail.contracts)?;
+    insert_links(tx, entity, "relatives", &detail.id, &detail.relatives)?;
+
+    for (position, section) in detail.abilities.iter().enumerate() {
+        tx.execute(
+            &entity.sql("INSERT INTO {entity}_ability_sections ({entity}_id, position, key, title) VALUES (?1, ?2, ?3, ?4)"),
+            params![detail.id, position, section.key, section.title],
+        )?;
+        insert_abilities(
+            tx,
+            entity,
+            &detail.id,
+            &section.key,
+            None,
+            &section.abilities,
+        )?;
+    }
+
+    for (position, warning) in detail.warnings.iter().enumerate() {
+        tx.execute(
+            &entity.sql("INSERT INTO {entity}_warnings ({entity}_id, position, field, message) VALUES (?1, ?2, ?3, ?4)"),
+            params![detail.id, position, warning.field, warning.message],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn insert_values(
+    tx: &Transaction,
+    entity: Entity,
+    field: &str,
+    id: &str,
+    values: &[String],
+) -> rusqlite::Result<()> {
+    let sql = entity.sql(&format!(
+        "INSERT INTO {{entity}}_{} ({{entity}}_id, position, value) VALUES (?1, ?2, ?3)",
+        field
+    ));
+    for (position, value) in values.iter().enumerate() {
+        tx.execute(&sql, params![id, position, value])?;
+    }
+
+    Ok(())
+}
+
+fn insert_links(
+    tx: &Transaction,
+    entity: Entity,
+    field: &str,
+    id: &str,
+    links: &[EntityLink],
+) -> rusqlite::Result<()> {
+    let sql = entity.sql(&format!(
+        "INSERT INTO {{entity}}_{} ({{entity}}_id, position, value, wiki_url, qualifier) VALUES (?1, ?2, ?3, ?4, ?5)",
+        field
+    ));
+    for (position, link) in links.iter().enumerate() {
+        tx.execute(
+            &sql,
+            params![id, position, link.name, link.wiki_url, link.qualifier],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn insert_abilities(
+    tx: &Transaction,
+    entity: Entity,
+    id: &str,
+    kind: &str,
+    parent_id: Option<i64>,
+    abilities: &[Ability],
+) -> rusqlite::Result<()> {
+    let sql = entity.sql("INSERT INTO {entity}_abilities ({entity}_id, parent_id, kind, position, name, description) VALUES (?1, ?2, ?3, ?4, ?5, ?6)");
+    for (position, ability) in abilities.iter().enumerate() {
+        tx.execute(
+            &sql,
+            params![
+                id,
+                parent_id,
+                kind,
+                position,
+                ability.name,
+                ability.description
+            ],
+        )?;
+
+        let ability_id = tx.last_insert_rowid();
+        insert_abilities(tx, entity, id, kind, Some(ability_id), &ability.abilities)?;
+    }
+
+    Ok(())
+}
+
+fn select_devil_detail(conn: &Connection, id: &str) -> rusqlite::Result<Option<DevilDetail>> {
+    let detail = conn
+        .query_row(
+            "SELECT d.devil_name, d.alias_name, d.wiki_url, d.category, dd.image_src, dd.gender, dd.birthplace, dd.status, dd.revision_id, dd.revision_timestamp FROM devil_details dd JOIN devils d ON d.id = dd.devil_id WHERE dd.devil_id = ?1",
+            params![id],
+            |row| {
+                Ok(DevilDetail {
+                    id: id.to_string(),
+                    devil_name: row.get(0)?,
+                    alias_name: row.get(1)?,
+                    wiki_url: row.get(2)?,
+                    category: row.get(3)?,
+                    names: HashMap::new(),
+                    image_src: row.get(4)?,
+                    images: Vec::new(),
+                    gender: row.get(5)?,
+                    birthplace: row.get(6)?,
+                    status: row.get(7)?,
+                    occupations: Vec::new(),
+                    affiliations: Vec::new(),
+                    contracts: Vec::new(),
+                    relatives: Vec::new(),
+                    abilities: Vec::new(),
+                    revision_id: row.get(8)?,
+                    revision_timestamp: row.get(9)?,
+                    warnings: Vec::new(),
+                })
+            },
+        )
+        .optional()?;
+
+    match detail {
+        Some(mut detail) => {
+            select_devil_rows(conn, &mut detail)?;
+            Ok(Some(detail))
+        }
+        None => Ok(None),
+    }
+}
+
+fn select_character_detail(
+    conn: &Connection,
+    id: &str,
+) -> rusqlite::Result<Option<CharacterDetail>> {
+    let detail = conn
+        .query_row(
+            "SELECT c.name, c.alias_name, c.wiki_url, c.category, cd.image_src, cd.gender, cd.birthplace, cd.status, cd.revision_id, cd.revision_timestamp FROM character_details cd JOIN characters c ON c.id = cd.character_id WHERE cd.character_id = ?1",
+            params![id],
+            |row| {
+                Ok(CharacterDetail {
+                    id: id.to_string(),
+                    name: row.get(0)?,
+                    alias_name: row.get(1)?,
+                    wiki_url: row.get(2)?,
+                    category: row.get(3)?,
+                    names: HashMap::new(),
+                    image_src: row.get(4)?,
+                    images: Vec::new(),
+                    gender: row.get(5)?,
+                    birthplace: row.get(6)?,
+                    status: row.get(7)?,
+                    occupations: Vec::new(),
+                    affiliations: Vec::new(),
+                    contracts: Vec::new(),
+                    relatives: Vec::new(),
+                    abilities: Vec::new(),
+                    revision_id: row.get(8)?,
+                    revision_timestamp: row.get(9)?,
+                    warnings: Vec::new(),
+                })
+            },
+        )
+        .optional()?;
+
+    match detail {
+        Some(mut detail) => {
+            select_character_rows(conn, &mut detail)?;
+            Ok(Some(detail))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Reads the names, images, lists, abilities and warnings of a detail whose own row is read
+#[duplicate_item(
+    select_detail_rows          detail_type         entity;
+    [select_devil_rows]         [DevilDetail]       [DEVIL];
+    [select_character_rows]     [CharacterDetail]   [CHARACTER];
+)]
+fn select_detail_rows(conn: &Connection, detail: &mut detail_type) -> rusqlite::Result<()> {
+    let id = detail.id.clone();
+
+    let mut stmt = conn.prepare(&entity.sql(
+        "SELECT language, {entity}_name, alias_name, reading, alias_reading FROM {entity}_names WHERE {entity}_id = ?1",
+    ))?;
+    let rows = stmt.query_map(params![id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            DevilName {
+                devil_name: row.get(1)?,
+                alias_name: row.get(2)?,
+                reading: row.get(3)?,
+                alias_reading: row.get(4)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (language, name) = row?;
+        detail.names.insert(language, name);
+    }
+
+    let mut stmt = conn.prepare(&entity.sql(
+        "SELECT src, label, caption FROM {entity}_images WHERE {entity}_id = ?1 ORDER BY position",
+    ))?;
+    let rows = stmt.query_map(params![id], |row| {
+        Ok(DevilImage {
+            src: row.get(0)?,
+            label: row.get(1)?,
+            caption: row.get(2)?,
+        })
+    })?;
+    detail.images = rows.collect::<rusqlite::Result<_>>()?;
+
+    detail.occupations = select_values(conn, entity, "occupations", &id)?;
+    detail.affiliations = select_values(conn, entity, "affiliations", &id)?;
+    detail.contracts = select_links(conn, entity, "contracts", &id)?;
+    detail.relatives = select_links(conn, entity, "relatives", &id)?;
+    let targets = select_link_targets(conn)?;
+    targets.resolve(&mut detail.contracts);
+    targets.resolve(&mut detail.relatives);
+    detail.abilities = select_abilities(conn, entity, &id)?;
+
+    let mut stmt = conn.prepare(&entity.sql(
+        "SELECT field, message FROM {entity}_warnings WHERE {entity}_id = ?1 ORDER BY position",
+    ))?;
+    let rows = stmt.query_map(params![id], |row| {
+        Ok(ParseWarning {
+            field: row.get(0)?,
+            message: row.get(1)?,
+        })
+    })?;
+    detail.warnings = rows.collect::<rusqlite::Result<_>>()?;
+
+    Ok(())
+}
+
+/// Revisions the stored details were scraped from, keyed by the id of the entity
+fn select_revisions(
+    conn: &Connection,
+    entity: Entity,
+) -> rusqlite::Result<HashMap<String, Revision>> {
+    let mut stmt = conn.prepare(&entity.sql(
+        "SELECT {entity}_id, revision_id, revision_timestamp FROM {entity}_details WHERE revision_id IS NOT NULL",
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            Revision {
+                id: row.get(1)?,
+                timestamp: row.get(2)?,
+            },
+        ))
+    })?;
+
+    rows.collect()
+}
+
+/// The reports of an entity are stored under the table of its listed pages, e.g. `devils`
+fn insert_report(conn: &Connection, entity: Entity, report: &ScrapeReport) -> Result<(), Error> {
+    let json = serde_json::to_string(report)?;
+
+    conn.execute(
+        "INSERT INTO scrape_reports (entity, started_at, report) VALUES (?1, ?2, ?3)",
+        params![entity.table, report.started_at.to_rfc3339(), json],
+    )
+    .map_err(Error::other)?;
+
+    Ok(())
+}
+
+fn select_latest_report(conn: &Connection, entity: Entity) -> Result<Option<ScrapeReport>, Error> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT report FROM scrape_reports WHERE entity = ?1 ORDER BY id DESC LIMIT 1",
+            params![entity.table],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(Error::other)?;
+
+    match json {
+        Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+        None => Ok(None),
+    }
+}
+
+fn select_values(
+    conn: &Connection,
+    entity: Entity,
+    field: &str,
+    id: &str,
+) -> rusqlite::Result<Vec<String>> {
+    let sql = entity.sql(&format!(
+        "SELECT value FROM {{entity}}_{} WHERE {{entity}}_id = ?1 ORDER BY position",
+        field
+    ));
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![id], |row| row.get(0))?;
+
+    rows.collect()
+}
+
+fn select_links(
+    conn: &Connection,
+    entity: Entity,
+    field: &str,
+    id: &str,
+) -> rusqlite::Result<Vec<EntityLink>> {
+    let sql = entity.sql(&format!(
+        "SELECT value, wiki_url, qualifier FROM {{entity}}_{} WHERE {{entity}}_id = ?1 ORDER BY position",
+        field
+    ));
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![id], |row| {
+        Ok(EntityLink {
+            name: row.get(0)?,
+            wiki_url: row.get(1)?,
+            entity_id: None,
+            qualifier: row.get(2)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Contracts and relatives of every stored entity keyed by its id, with one query per table
+/// instead of one per entity
+fn select_all_links(
+    conn: &Connection,
+    entity: Entity,
+) -> rusqlite::Result<HashMap<String, EntityLinks>> {
+    let mut links: HashMap<String, EntityLinks> = HashMap::new();
+    for field in ["contracts", "relatives"] {
+        let sql = entity.sql(&format!(
+            "SELECT {{entity}}_id, value, wiki_url, qualifier FROM {{entity}}_{} ORDER BY {{entity}}_id, position",
+            field
+        ));
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                EntityLink {
+                    name: row.get(1)?,
+                    wiki_url: row.get(2)?,
+                    entity_id: None,
+                    qualifier: row.get(3)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (id, link) = row?;
+            let entity_links = links.entry(id).or_default();
+            match field {
+                "contracts" => entity_links.contracts.push(link),
+                _ => entity_links.relatives.push(link),
+            }
+        }
+    }
+
+    let targets = select_link_targets(conn)?;
+    for entity_links in links.values_mut() {
+        targets.resolve(&mut entity_links.contracts);
+        targets.resolve(&mut entity_links.relatives);
+    }
+
+    Ok(links)
+}
+
+/// Pages of the stored devils and characters, the links are resolved when read so that they
+/// follow the entities scraped after them
+fn select_link_targets(conn: &Connection) -> rusqlite::Result<LinkTargets> {
+    let mut stmt = conn
+        .prepare("SELECT wiki_url, id FROM devils UNION ALL SELECT wiki_url, id FROM characters")?;
+    let mut rows = stmt.query([])?;
+
+    let mut targets = LinkTargets::default();
+    while let Some(row) = rows.next()? {
+        targets.insert(row.get_ref(0)?.as_str()?, row.get_ref(1)?.as_str()?);
+    }
+
+    Ok(targets)
+}
+
+struct AbilityRow {
+    id: i64,
+    parent_id: Option<i64>,
+    kind: String,
+    name: String,
+    description: String,
+}
+
+fn select_abilities(
+    conn: &Connection,
+    entity: Entity,
+    id: &str,
+) -> rusqlite::Result<Vec<AbilitySection>> {
+    let mut stmt = conn.prepare(&entity.sql(
+        "SELECT key, title FROM {entity}_ability_sections WHERE {entity}_id = ?1 ORDER BY position",
+    ))?;
+    let mut sections = stmt
+        .query_map(params![id], |row| {
+            Ok(AbilitySection {
+                key: row.get(0)?,
+                title: row.get(1)?,
+                abilities: Vec::new(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<AbilitySection>>>()?;
+
+    let mut stmt = conn.prepare(&entity.sql(
+        "SELECT id, parent_id, kind, name, description FROM {entity}_abilities WHERE {entity}_id = ?1 ORDER BY position",
+    ))?;
+    let rows = stmt.query_map(params![id], |row| {
+        Ok(AbilityRow {
+            id: row.get(0)?,
+            parent_id: row.get(1)?,
+            kind: row.get(2)?,
+            name: row.get(3)?,
+            description: row.get(4)?,
+        })
+    })?;
+
+    // key: parent ability id, value: the child abilities ordered by position
+    let mut children: HashMap<Option<i64>, Vec<AbilityRow>> = HashMap::new();
+    for row in rows {
+        let row = row?;
+        children.entry(row.parent_id).or_default().push(row);
+    }
+
+    let mut roots = children.remove(&None).unwrap_or_default();
+    // Abilities stored before their sections are sections titled by their kind
+    roots.sort_by(|a, b| a.kind.cmp(&b.kind));
+    for row in roots {
+        let kind = row.kind.clone();
+        let ability = build_ability(row, &mut children);
+        match sections.iter_mut().find(|section| section.key == kind) {
+            Some(section) => section.abilities.push(ability),
+            None => sections.push(AbilitySection {
+                key: kind.clone(),
+                title: kind,
+                abilities: vec![ability],
+            }),
+        }
+    }
+
+    Ok(sections)
+}
+
+fn build_ability(row: AbilityRow, children: &mut HashMap<Option<i64>, Vec<AbilityRow>>) -> Ability {
+    let child_rows = children.remove(&Some(row.id)).unwrap_or_default();
+
+    Ability {
+        name: row.name,
+        description: row.description,
+        abilities: child_rows
+            .into_iter()
+            .map(|child| build_ability(child, children))
+            .collect(),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod devil;
+
+pub use devil::*;
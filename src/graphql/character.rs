@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use async_graphql::{ComplexObject, Context, SimpleObject};
+use chrono::{DateTime, Utc};
+
+use crate::{models, services::CharacterService};
+
+use super::{AbilityGroup, DevilImage, DevilName, EntityLink, Gender, ParseWarning, Status};
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Character {
+    pub id: String,
+    pub name: String,
+    pub alias_name: Option<String>,
+    pub wiki_url: String,
+    pub category: String,
+}
+
+#[ComplexObject]
+impl Character {
+    /// Detailed information of the character, scraped from its wiki page
+    async fn detail(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<CharacterDetail>> {
+        let service = ctx.data::<Arc<CharacterService>>()?;
+        let detail = service.get_character_by_id(&self.id).await?;
+
+        Ok(detail.map(CharacterDetail::from))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct CharacterDetail {
+    pub id: String,
+    pub name: String,
+    pub alias_name: Option<String>,
+    pub wiki_url: String,
+    pub category: String,
+    pub names: Vec<DevilName>,
+    /// Main infobox image, the first of `images`
+    pub image_src: Option<String>,
+    pub images: Vec<DevilImage>,
+    pub gender: Option<Gender>,
+    /// Gender of the infobox when `gender` is `OTHER`
+    pub other_gender: Option<String>,
+    pub birthplace: Option<String>,
+    pub status: Option<Status>,
+    /// Status of the infobox when `status` is `OTHER`
+    pub other_status: Option<String>,
+    pub occupations: Vec<String>,
+    pub affiliations: Vec<String>,
+    pub contracts: Vec<EntityLink>,
+    pub relatives: Vec<EntityLink>,
+    pub abilities: Vec<AbilityGroup>,
+    /// Revision of the wiki page the detail was scraped from
+    pub revision_id: Option<u64>,
+    pub revision_timestamp: Option<DateTime<Utc>>,
+    /// Fields of the infobox that couldn't be parsed
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl From<models::Character> for Character {
+    fn from(character: models::Character) -> Self {
+        Character {
+            id: character.id,
+            name: character.name,
+            alias_name: character.alias_name,
+            wiki_url: character.wiki_url,
+            category: character.category,
+        }
+    }
+}
+
+impl From<models::CharacterDetail> for CharacterDetail {
+    fn from(detail: models::CharacterDetail) -> Self {
+        let mut names = detail
+            .names
+            .into_iter()
+            .map(|(language, name)| DevilName {
+                language,
+                devil_name: name.devil_name,
+                alias_name: name.alias_name,
+                reading: name.reading,
+                alias_reading: name.alias_reading,
+            })
+            .collect::<Vec<DevilName>>();
+        names.sort_by(|a, b| a.language.cmp(&b.language));
+
+        CharacterDetail {
+            id: detail.id,
+            name: detail.name,
+            alias_name: detail.alias_name,
+            wiki_url: detail.wiki_url,
+            category: detail.category,
+            names,
+            image_src: detail.image_src,
+            images: detail.images.into_iter().map(DevilImage::from).collect(),
+            gender: detail.gender.as_ref().map(Gender::from),
+            other_gender: detail
+                .gender
+                .as_ref()
+                .and_then(|gender| gender.other())
+                .map(str::to_string),
+            birthplace: detail.birthplace,
+            status: detail.status.as_ref().map(Status::from),
+            other_status: detail
+                .status
+                .as_ref()
+                .and_then(|status| status.other())
+                .map(str::to_string),
+            occupations: detail.occupations,
+            affiliations: detail.affiliations,
+            contracts: detail.contracts.into_iter().map(EntityLink::from).collect(),
+            relatives: detail.relatives.into_iter().map(EntityLink::from).collect(),
+            abilities: detail
+                .abilities
+                .into_iter()
+                .map(AbilityGroup::from)
+                .collect(),
+            revision_id: detail.revision_id,
+            revision_timestamp: detail.revision_timestamp,
+            warnings: detail
+                .warnings
+                .into_iter()
+                .map(ParseWarning::from)
+                .collect(),
+        }
+    }
+}
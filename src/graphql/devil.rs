@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use async_graphql::{ComplexObject, Context, Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+
+use crate::{models, services::DevilService};
+
+use super::ParseWarning;
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Devil {
+    pub id: String,
+    pub devil_name: String,
+    pub alias_name: Option<String>,
+    pub wiki_url: String,
+    pub category: DevilCategory,
+    /// Category of the wiki when `category` is `OTHER`
+    pub other_category: Option<String>,
+}
+
+#[ComplexObject]
+impl Devil {
+    /// Detailed information of the devil, scraped from its wiki page
+    async fn detail(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<DevilDetail>> {
+        let service = ctx.data::<Arc<DevilService>>()?;
+        let detail = service.get_devil_by_id(&self.id).await?;
+
+        Ok(detail.map(DevilDetail::from))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct DevilDetail {
+    pub id: String,
+    pub devil_name: String,
+    pub alias_name: Option<String>,
+    pub wiki_url: String,
+    pub category: DevilCategory,
+    /// Category of the wiki when `category` is `OTHER`
+    pub other_category: Option<String>,
+    pub names: Vec<DevilName>,
+    /// Main infobox image, the first of `images`
+    pub image_src: Option<String>,
+    pub images: Vec<DevilImage>,
+    pub gender: Option<Gender>,
+    /// Gender of the infobox when `gender` is `OTHER`
+    pub other_gender: Option<String>,
+    pub birthplace: Option<String>,
+    pub status: Option<Status>,
+    /// Status of the infobox when `status` is `OTHER`
+    pub other_status: Option<String>,
+    pub occupations: Vec<String>,
+    pub affiliations: Vec<String>,
+    pub contracts: Vec<EntityLink>,
+    pub relatives: Vec<EntityLink>,
+    pub abilities: Vec<AbilityGroup>,
+    /// Revision of the wiki page the detail was scraped from
+    pub revision_id: Option<u64>,
+    pub revision_timestamp: Option<DateTime<Utc>>,
+    /// Fields of the infobox that couldn't be parsed
+    pub warnings: Vec<ParseWarning>,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum DevilCategory {
+    Normal,
+    Primal,
+    Reincarnated,
+    Fiend,
+    Hybrid,
+    Other,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Alive,
+    Deceased,
+    Unknown,
+    Other,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    Male,
+    Female,
+    Other,
+}
+
+#[derive(SimpleObject)]
+pub struct DevilName {
+    /// Language code of the name, e.g. `kanji` or `romaji`
+    pub language: String,
+    pub devil_name: String,
+    pub alias_name: Option<String>,
+    /// Furigana reading of the kanji name
+    pub reading: Option<String>,
+    /// Furigana reading of the kanji alias
+    pub alias_reading: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct DevilImage {
+    pub src: String,
+    /// Tab title of an alternate image, e.g. `Manga` or `Anime`
+    pub label: Option<String>,
+    pub caption: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct EntityLink {
+    pub name: String,
+    /// Wiki page of the linked entity, if it has one
+    pub wiki_url: Option<String>,
+    /// Id of the linked devil or character, if its page is in the dataset
+    pub entity_id: Option<String>,
+    /// Relationship given next to the link, e.g. `sister`
+    pub qualifier: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct AbilityGroup {
+    /// Kind of the abilities, e.g. `physical` or `devil`
+    pub kind: String,
+    /// Heading the abilities are listed under on the wiki, e.g. `Devil Powers`
+    pub title: String,
+    pub abilities: Vec<Ability>,
+}
+
+#[derive(SimpleObject)]
+pub struct Ability {
+    pub name: String,
+    pub description: String,
+    pub abilities: Vec<Ability>,
+}
+
+impl From<models::Devil> for Devil {
+    fn from(devil: models::Devil) -> Self {
+        Devil {
+            id: devil.id,
+            devil_name: devil.devil_name,
+            alias_name: devil.alias_name,
+            wiki_url: devil.wiki_url,
+            category: DevilCategory::from(&devil.category),
+            other_category: devil.category.other().map(str::to_string),
+        }
+    }
+}
+
+impl From<models::DevilDetail> for DevilDetail {
+    fn from(detail: models::DevilDetail) -> Self {
+        let mut names = detail
+            .names
+            .into_iter()
+            .map(|(language, name)| DevilName {
+                language,
+                devil_name: name.devil_name,
+                alias_name: name.alias_name,
+                reading: name.reading,
+                alias_reading: name.alias_reading,
+            })
+            .collect::<Vec<DevilName>>();
+        names.sort_by(|a, b| a.language.cmp(&b.language));
+
+        DevilDetail {
+            id: detail.id,
+            devil_name: detail.devil_name,
+            alias_name: detail.alias_name,
+            wiki_url: detail.wiki_url,
+            category: DevilCategory::from(&detail.category),
+            other_category: detail.category.other().map(str::to_string),
+            names,
+            image_src: detail.image_src,
+            images: detail.images.into_iter().map(DevilImage::from).collect(),
+            gender: detail.gender.as_ref().map(Gender::from),
+            other_gender: detail
+                .gender
+                .as_ref()
+                .and_then(|gender| gender.other())
+                .map(str::to_string),
+            birthplace: detail.birthplace,
+            status: detail.status.as_ref().map(Status::from),
+            other_status: detail
+                .status
+                .as_ref()
+                .and_then(|status| status.other())
+                .map(str::to_string),
+            occupations: detail.occupations,
+            affiliations: detail.affiliations,
+            contracts: detail.contracts.into_iter().map(EntityLink::from).collect(),
+            relatives: detail.relatives.into_iter().map(EntityLink::from).collect(),
+            abilities: detail
+                .abilities
+                .into_iter()
+                .map(AbilityGroup::from)
+                .collect(),
+            revision_id: detail.revision_id,
+            revision_timestamp: detail.revision_timestamp,
+            warnings: detail
+                .warnings
+                .into_iter()
+                .map(ParseWarning::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<models::AbilitySection> for AbilityGroup {
+    fn from(section: models::AbilitySection) -> Self {
+        AbilityGroup {
+            kind: section.key,
+            title: section.title,
+            abilities: section.abilities.into_iter().map(Ability::from).collect(),
+        }
+    }
+}
+
+impl From<&models::DevilCategory> for DevilCategory {
+    fn from(category: &models::DevilCategory) -> Self {
+        match category {
+            models::DevilCategory::Normal => DevilCategory::Normal,
+            models::DevilCategory::Primal => DevilCategory::Primal,
+            models::DevilCategory::Reincarnated => DevilCategory::Reincarnated,
+            models::DevilCategory::Fiend => DevilCategory::Fiend,
+            models::DevilCategory::Hybrid => DevilCategory::Hybrid,
+            models::DevilCategory::Other(_) => DevilCategory::Other,
+        }
+    }
+}
+
+impl From<&models::Status> for Status {
+    fn from(status: &models::Status) -> Self {
+        match status {
+            models::Status::Alive => Status::Alive,
+            models::Status::Deceased => Status::Deceased,
+            models::Status::Unknown => Status::Unknown,
+            models::Status::Other(_) => Status::Other,
+        }
+    }
+}
+
+impl From<&models::Gender> for Gender {
+    fn from(gender: &models::Gender) -> Self {
+        match gender {
+            models::Gender::Male => Gender::Male,
+            models::Gender::Female => Gender::Female,
+            models::Gender::Other(_) => Gender::Other,
+        }
+    }
+}
+
+impl From<models::DevilImage> for DevilImage {
+    fn from(image: models::DevilImage) -> Self {
+        DevilImage {
+            src: image.src,
+            label: image.label,
+            caption: image.caption,
+        }
+    }
+}
+
+impl From<models::EntityLink> for EntityLink {
+    fn from(link: models::EntityLink) -> Self {
+        EntityLink {
+            name: link.name,
+            wiki_url: link.wiki_url,
+            entity_id: link.entity_id,
+            qualifier: link.qualifier,
+        }
+    }
+}
+
+impl From<models::Ability> for Ability {
+    fn from(ability: models::Ability) -> Self {
+        Ability {
+            name: ability.name,
+            description: ability.description,
+            abilities: ability.abilities.into_iter().map(Ability::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use async_graphql::{Enum, SimpleObject};
+
+use crate::models;
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Devil,
+    Character,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// A devil and its contracted human
+    Contract,
+    /// An entity and one of its relatives
+    Relative,
+}
+
+#[derive(SimpleObject)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    pub kind: NodeKind,
+    pub wiki_url: String,
+}
+
+#[derive(SimpleObject)]
+pub struct GraphEdge {
+    /// Id of the entity whose infobox has the link
+    pub source: String,
+    /// Id of the linked entity
+    pub target: String,
+    pub kind: EdgeKind,
+    /// Relationship given next to the link, e.g. `sister`
+    pub qualifier: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(SimpleObject)]
+pub struct Neighbor {
+    pub node: GraphNode,
+    /// Edge linking the entity to its neighbor
+    pub edge: GraphEdge,
+}
+
+#[derive(SimpleObject)]
+pub struct GraphPath {
+    /// Entities of the path from the first to the last one
+    pub nodes: Vec<GraphNode>,
+    /// Edges of the path, the nth edge links the nth and the next entity
+    pub edges: Vec<GraphEdge>,
+}
+
+impl From<models::NodeKind> for NodeKind {
+    fn from(kind: models::NodeKind) -> Self {
+        match kind {
+            models::NodeKind::Devil => NodeKind::Devil,
+            models::NodeKind::Character => NodeKind::Character,
+        }
+    }
+}
+
+impl From<models::EdgeKind> for EdgeKind {
+    fn from(kind: models::EdgeKind) -> Self {
+        match kind {
+            models::EdgeKind::Contract => EdgeKind::Contract,
+            models::EdgeKind::Relative => EdgeKind::Relative,
+        }
+    }
+}
+
+impl From<EdgeKind> for models::EdgeKind {
+    fn from(kind: EdgeKind) -> Self {
+        match kind {
+            EdgeKind::Contract => models::EdgeKind::Contract,
+            EdgeKind::Relative => models::EdgeKind::Relative,
+        }
+    }
+}
+
+impl From<models::GraphNode> for GraphNode {
+    fn from(node: models::GraphNode) -> Self {
+        GraphNode {
+            id: node.id,
+            name: node.name,
+            kind: node.kind.into(),
+            wiki_url: node.wiki_url,
+        }
+    }
+}
+
+impl From<models::GraphEdge> for GraphEdge {
+    fn from(edge: models::GraphEdge) -> Self {
+        GraphEdge {
+            source: edge.source,
+            target: edge.target,
+            kind: edge.kind.into(),
+            qualifier: edge.qualifier,
+        }
+    }
+}
+
+impl From<models::Graph> for Graph {
+    fn from(graph: models::Graph) -> Self {
+        Graph {
+            nodes: graph.nodes.into_iter().map(GraphNode::from).collect(),
+            edges: graph.edges.into_iter().map(GraphEdge::from).collect(),
+        }
+    }
+}
+
+impl From<models::Neighbor> for Neighbor {
+    fn from(neighbor: models::Neighbor) -> Self {
+        Neighbor {
+            node: neighbor.node.into(),
+            edge: neighbor.edge.into(),
+        }
+    }
+}
+
+impl From<models::GraphPath> for GraphPath {
+    fn from(path: models::GraphPath) -> Self {
+        GraphPath {
+            nodes: path.nodes.into_iter().map(GraphNode::from).collect(),
+            edges: path.edges.into_iter().map(GraphEdge::from).collect(),
+        }
+    }
+}
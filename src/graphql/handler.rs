@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use async_graphql::{http::GraphiQLSource, EmptyMutation, EmptySubscription, Schema};
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::{
@@ -5,12 +7,22 @@ use axum::{
     response::{self, IntoResponse},
 };
 
+use crate::services::{CharacterService, DevilService, GraphService};
+
 use super::Query;
 
 pub type RootSchema = Schema<Query, EmptyMutation, EmptySubscription>;
 
-pub fn schema() -> RootSchema {
-    Schema::build(Query, EmptyMutation, EmptySubscription).finish()
+pub fn schema(
+    devil_service: Arc<DevilService>,
+    character_service: Arc<CharacterService>,
+    graph_service: Arc<GraphService>,
+) -> RootSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(devil_service)
+        .data(character_service)
+        .data(graph_service)
+        .finish()
 }
 
 pub async fn graphiql() -> impl IntoResponse {
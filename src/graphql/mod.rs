@@ -1,5 +1,13 @@
+pub mod character;
+pub mod devil;
+pub mod graph;
 pub mod handler;
 pub mod query;
+pub mod report;
 
+pub use character::*;
+pub use devil::*;
+pub use graph::*;
 pub use handler::*;
 pub use query::*;
+pub use report::*;
@@ -1,4 +1,16 @@
-use async_graphql::Object;
+use std::sync::Arc;
+
+use async_graphql::{Context, Object};
+
+use crate::{
+    models,
+    services::{CharacterService, DevilService, GraphService},
+};
+
+use super::{
+    Character, Devil, DevilCategory, EdgeKind, Graph, GraphPath, Neighbor, ReportEntity,
+    ScrapeReport,
+};
 
 pub struct Query;
 
@@ -7,4 +19,118 @@ impl Query {
     async fn health(&self) -> &'static str {
         "Hello, World!"
     }
+
+    /// Devils of the given category, every devil when `category` is missing
+    async fn devils(
+        &self,
+        ctx: &Context<'_>,
+        category: Option<DevilCategory>,
+    ) -> async_graphql::Result<Vec<Devil>> {
+        let service = ctx.data::<Arc<DevilService>>()?;
+        let devils = service.get_devils().await?;
+
+        Ok(devils
+            .into_iter()
+            .map(Devil::from)
+            .filter(|devil| category.is_none_or(|category| devil.category == category))
+            .collect())
+    }
+
+    async fn devil(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Devil>> {
+        let service = ctx.data::<Arc<DevilService>>()?;
+        let detail = service.get_devil_by_id(&id).await?;
+
+        Ok(detail.as_ref().map(models::Devil::from).map(Devil::from))
+    }
+
+    /// Humans and other characters that aren't devils
+    async fn characters(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Character>> {
+        let service = ctx.data::<Arc<CharacterService>>()?;
+        let characters = service.get_characters().await?;
+
+        Ok(characters.into_iter().map(Character::from).collect())
+    }
+
+    async fn character(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> async_graphql::Result<Option<Character>> {
+        let service = ctx.data::<Arc<CharacterService>>()?;
+        let detail = service.get_character_by_id(&id).await?;
+
+        Ok(detail
+            .as_ref()
+            .map(models::Character::from)
+            .map(Character::from))
+    }
+
+    /// Entities linked to the given devil or character by an edge of one of the kinds, every
+    /// kind is followed when `kinds` is missing
+    async fn neighbors(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        kinds: Option<Vec<EdgeKind>>,
+    ) -> async_graphql::Result<Option<Vec<Neighbor>>> {
+        let service = ctx.data::<Arc<GraphService>>()?;
+        let neighbors = service.get_neighbors(&id, &edge_kinds(kinds)).await?;
+
+        Ok(neighbors.map(|neighbors| neighbors.into_iter().map(Neighbor::from).collect()))
+    }
+
+    /// Shortest path between two entities, null if they aren't connected
+    async fn shortest_path(
+        &self,
+        ctx: &Context<'_>,
+        from: String,
+        to: String,
+        kinds: Option<Vec<EdgeKind>>,
+    ) -> async_graphql::Result<Option<GraphPath>> {
+        let service = ctx.data::<Arc<GraphService>>()?;
+        let path = service
+            .get_shortest_path(&from, &to, &edge_kinds(kinds))
+            .await?;
+
+        Ok(path.map(GraphPath::from))
+    }
+
+    /// Connected components of the relationship graph from the largest to the smallest,
+    /// the entities without any edge of the kinds are left out
+    async fn components(
+        &self,
+        ctx: &Context<'_>,
+        kinds: Option<Vec<EdgeKind>>,
+    ) -> async_graphql::Result<Vec<Graph>> {
+        let service = ctx.data::<Arc<GraphService>>()?;
+        let components = service.get_components(&edge_kinds(kinds)).await?;
+
+        Ok(components.into_iter().map(Graph::from).collect())
+    }
+
+    /// Report of the latest scraper run of the entity, the devils when `entity` is missing
+    async fn scrape_report(
+        &self,
+        ctx: &Context<'_>,
+        entity: Option<ReportEntity>,
+    ) -> async_graphql::Result<Option<ScrapeReport>> {
+        let report = match entity.unwrap_or(ReportEntity::Devils) {
+            ReportEntity::Devils => ctx.data::<Arc<DevilService>>()?.get_latest_report().await?,
+            ReportEntity::Characters => {
+                ctx.data::<Arc<CharacterService>>()?
+                    .get_latest_report()
+                    .await?
+            }
+        };
+
+        Ok(report.map(ScrapeReport::from))
+    }
+}
+
+fn edge_kinds(kinds: Option<Vec<EdgeKind>>) -> Vec<models::EdgeKind> {
+    kinds
+        .unwrap_or_default()
+        .into_iter()
+        .map(models::EdgeKind::from)
+        .collect()
 }
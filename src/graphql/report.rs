@@ -0,0 +1,92 @@
+use async_graphql::{Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+
+use crate::models;
+
+#[derive(SimpleObject)]
+pub struct ScrapeReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub succeeded: Vec<ScrapedPage>,
+    pub failed: Vec<FailedPage>,
+    /// Ids of the pages that didn't change since the last scrape
+    pub unchanged: Vec<String>,
+}
+
+/// Entity a scrape report was written for, the devils and the characters are scraped apart
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum ReportEntity {
+    Devils,
+    Characters,
+}
+
+#[derive(SimpleObject)]
+pub struct ScrapedPage {
+    pub id: String,
+    pub wiki_url: String,
+    pub category: String,
+    pub duration_ms: u64,
+    /// Fields of the page that couldn't be parsed without failing the scrape
+    pub warnings: Vec<ParseWarning>,
+}
+
+#[derive(SimpleObject)]
+pub struct FailedPage {
+    pub id: String,
+    pub wiki_url: String,
+    pub category: String,
+    /// Kind of the failure, e.g. `network` or `missing_section`
+    pub error_kind: String,
+    pub error: String,
+    pub duration_ms: u64,
+}
+
+#[derive(SimpleObject)]
+pub struct ParseWarning {
+    pub field: String,
+    pub message: String,
+}
+
+impl From<models::ScrapeReport> for ScrapeReport {
+    fn from(report: models::ScrapeReport) -> Self {
+        ScrapeReport {
+            started_at: report.started_at,
+            finished_at: report.finished_at,
+            duration_ms: report.duration_ms(),
+            succeeded: report
+                .succeeded
+                .into_iter()
+                .map(|page| ScrapedPage {
+                    id: page.id,
+                    wiki_url: page.wiki_url,
+                    category: page.category,
+                    duration_ms: page.duration_ms,
+                    warnings: page.warnings.into_iter().map(ParseWarning::from).collect(),
+                })
+                .collect(),
+            failed: report
+                .failed
+                .into_iter()
+                .map(|page| FailedPage {
+                    id: page.id,
+                    wiki_url: page.wiki_url,
+                    category: page.category,
+                    error_kind: page.error_kind,
+                    error: page.error,
+                    duration_ms: page.duration_ms,
+                })
+                .collect(),
+            unchanged: report.unchanged,
+        }
+    }
+}
+
+impl From<models::ParseWarning> for ParseWarning {
+    fn from(warning: models::ParseWarning) -> Self {
+        ParseWarning {
+            field: warning.field,
+            message: warning.message,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+
+use crate::{
+    models::{Character, CharacterDetail},
+    services::CharacterService,
+};
+
+use super::ApiError;
+
+pub async fn list_characters(
+    Extension(service): Extension<Arc<CharacterService>>,
+) -> Result<Json<Vec<Character>>, ApiError> {
+    let characters = service.get_characters().await?;
+    Ok(Json(characters))
+}
+
+pub async fn get_character(
+    Extension(service): Extension<Arc<CharacterService>>,
+    Path(id): Path<String>,
+) -> Result<Json<CharacterDetail>, ApiError> {
+    match service.get_character_by_id(&id).await? {
+        Some(character) => Ok(Json(character)),
+        None => Err(ApiError::NotFound(format!("Character with id {}", id))),
+    }
+}
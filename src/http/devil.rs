@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::{
+    models::{Devil, DevilCategory, DevilDetail},
+    services::DevilService,
+};
+
+use super::ApiError;
+
+#[derive(Debug, Deserialize)]
+pub struct DevilParams {
+    /// Only the devils of the category are listed, e.g. `primal`
+    pub category: Option<DevilCategory>,
+}
+
+pub async fn list_devils(
+    Extension(service): Extension<Arc<DevilService>>,
+    Query(params): Query<DevilParams>,
+) -> Result<Json<Vec<Devil>>, ApiError> {
+    let mut devils = service.get_devils().await?;
+    if let Some(category) = &params.category {
+        devils.retain(|devil| &devil.category == category);
+    }
+
+    Ok(Json(devils))
+}
+
+pub async fn get_devil(
+    Extension(service): Extension<Arc<DevilService>>,
+    Path(id): Path<String>,
+) -> Result<Json<DevilDetail>, ApiError> {
+    match service.get_devil_by_id(&id).await? {
+        Some(devil) => Ok(Json(devil)),
+        None => Err(ApiError::NotFound(format!("Devil with id {}", id))),
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fmt, io};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    /// The local store couldn't be read
+    Internal(io::Error),
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+impl From<io::Error> for ApiError {
+    fn from(err: io::Error) -> Self {
+        ApiError::Internal(err)
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ApiError::BadRequest(ref reason) => write!(f, "Bad request: {}", reason),
+            ApiError::NotFound(ref resource) => write!(f, "{} is not found", resource),
+            ApiError::Internal(ref err) => write!(f, "Unable to read the stored data: {}", err),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        // The error of the store is only logged, its text isn't meant for the clients
+        let (status, message) = match self {
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            ApiError::Internal(_) => {
+                tracing::error!("{}", self);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal server error".to_string(),
+                )
+            }
+        };
+
+        let body = Json(ErrorResponse { message });
+
+        (status, body).into_response()
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::{
+    models::{EdgeKind, Graph, GraphPath, Neighbor},
+    services::GraphService,
+};
+
+use super::ApiError;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    #[default]
+    Json,
+    GraphMl,
+    Dot,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphParams {
+    #[serde(default)]
+    pub format: GraphFormat,
+    /// Comma separated kinds of the edges followed, e.g. `contract,relative`, every kind is
+    /// followed when missing
+    pub kinds: Option<String>,
+}
+
+pub async fn get_graph(
+    Extension(service): Extension<Arc<GraphService>>,
+    Query(params): Query<GraphParams>,
+) -> Result<Response, ApiError> {
+    let kinds = parse_kinds(&params)?;
+
+    let graph = service.get_graph(&kinds).await?;
+
+    let response = match params.format {
+        GraphFormat::Json => Json(graph).into_response(),
+        GraphFormat::GraphMl => (
+            [(header::CONTENT_TYPE, "application/graphml+xml")],
+            graph.to_graphml(),
+        )
+            .into_response(),
+        GraphFormat::Dot => (
+            [(header::CONTENT_TYPE, "text/vnd.graphviz")],
+            graph.to_dot(),
+        )
+            .into_response(),
+    };
+
+    Ok(response)
+}
+
+pub async fn get_neighbors(
+    Extension(service): Extension<Arc<GraphService>>,
+    Path(id): Path<String>,
+    Query(params): Query<GraphParams>,
+) -> Result<Json<Vec<Neighbor>>, ApiError> {
+    let kinds = parse_kinds(&params)?;
+
+    match service.get_neighbors(&id, &kinds).await? {
+        Some(neighbors) => Ok(Json(neighbors)),
+        None => Err(ApiError::NotFound(format!("Entity with id {}", id))),
+    }
+}
+
+pub async fn get_shortest_path(
+    Extension(service): Extension<Arc<GraphService>>,
+    Path((from, to)): Path<(String, String)>,
+    Query(params): Query<GraphParams>,
+) -> Result<Json<GraphPath>, ApiError> {
+    let kinds = parse_kinds(&params)?;
+
+    match service.get_shortest_path(&from, &to, &kinds).await? {
+        Some(path) => Ok(Json(path)),
+        None => Err(ApiError::NotFound(format!(
+            "Path between {} and {}",
+            from, to
+        ))),
+    }
+}
+
+pub async fn get_components(
+    Extension(service): Extension<Arc<GraphService>>,
+    Query(params): Query<GraphParams>,
+) -> Result<Json<Vec<Graph>>, ApiError> {
+    let kinds = parse_kinds(&params)?;
+
+    let components = service.get_components(&kinds).await?;
+    Ok(Json(components))
+}
+
+fn parse_kinds(params: &GraphParams) -> Result<Vec<EdgeKind>, ApiError> {
+    match &params.kinds {
+        Some(kinds) => kinds
+            .split(',')
+            .filter(|kind| !kind.trim().is_empty())
+            .map(|kind| kind.parse().map_err(ApiError::BadRequest))
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
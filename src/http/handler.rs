@@ -1,29 +1,48 @@
-use std::net::SocketAddr;
+use std::{fs, net::SocketAddr, sync::Arc};
 
 use axum::{extract::Extension, routing, Router, Server};
+use serde::de::DeserializeOwned;
 
-use crate::{config::Config, graphql};
+use crate::{
+    config::Config,
+    database::SqliteDevilRepository,
+    graphql,
+    models::Dataset,
+    services::{CharacterService, DevilService, GraphService},
+};
 
-macro_rules! fatal {
-    ($($tt:tt)*) => {{
-        use std::io::Write;
-        writeln!(&mut ::std::io::stderr(), $($tt)*).unwrap();
-        ::std::process::exit(1)
-    }}
-}
+use super::{character, devil, graph, report};
 
 pub async fn run() {
     let config = Config::new().unwrap_or_else(|err| fatal!("{}", err));
 
-    let graphql_schema = graphql::handler::schema();
+    let repository = SqliteDevilRepository::open(&config.database_path)
+        .unwrap_or_else(|err| fatal!("Unable to open {}: {}", config.database_path, err));
 
-    let app = Router::new()
-        .route("/", routing::get(root))
-        .route(
-            "/graphql",
-            routing::get(graphql::handler::graphiql).post(graphql::handler::handle),
-        )
-        .layer(Extension(graphql_schema));
+    let repository = Arc::new(repository);
+    // The API only serves the stored data, it never scrapes the wiki
+    let devil_service = Arc::new(DevilService::read_only(repository.clone()));
+    let character_service = Arc::new(CharacterService::read_only(repository.clone()));
+    let graph_service = Arc::new(GraphService::new(repository.clone(), repository));
+
+    if let Some(path) = &config.devils_dataset_path {
+        let dataset = load_dataset(path);
+        devil_service
+            .load(&dataset)
+            .await
+            .unwrap_or_else(|err| fatal!("Unable to load {}: {}", path, err));
+        tracing::info!("Loaded {} devils from {}", dataset.entries.len(), path);
+    }
+    if let Some(path) = &config.characters_dataset_path {
+        let dataset = load_dataset(path);
+        character_service
+            .load(&dataset)
+            .await
+            .unwrap_or_else(|err| fatal!("Unable to load {}: {}", path, err));
+        tracing::info!("Loaded {} characters from {}", dataset.entries.len(), path);
+    }
+
+    let app = router(devil_service, character_service, graph_service);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], config.port));
 
@@ -35,6 +54,48 @@ pub async fn run() {
         .unwrap();
 }
 
+/// Routes of the API, served from the data of the services
+pub fn router(
+    devil_service: Arc<DevilService>,
+    character_service: Arc<CharacterService>,
+    graph_service: Arc<GraphService>,
+) -> Router {
+    let graphql_schema = graphql::handler::schema(
+        devil_service.clone(),
+        character_service.clone(),
+        graph_service.clone(),
+    );
+
+    Router::new()
+        .route("/", routing::get(root))
+        .route("/devils", routing::get(devil::list_devils))
+        .route("/devils/:id", routing::get(devil::get_devil))
+        .route("/characters", routing::get(character::list_characters))
+        .route("/characters/:id", routing::get(character::get_character))
+        .route("/graph", routing::get(graph::get_graph))
+        .route("/graph/neighbors/:id", routing::get(graph::get_neighbors))
+        .route(
+            "/graph/path/:from/:to",
+            routing::get(graph::get_shortest_path),
+        )
+        .route("/graph/components", routing::get(graph::get_components))
+        .route("/scrape-report", routing::get(report::get_latest_report))
+        .route(
+            "/graphql",
+            routing::get(graphql::handler::graphiql).post(graphql::handler::handle),
+        )
+        .layer(Extension(graphql_schema))
+        .layer(Extension(devil_service))
+        .layer(Extension(character_service))
+        .layer(Extension(graph_service))
+}
+
+fn load_dataset<T: DeserializeOwned>(path: &str) -> Dataset<T> {
+    let json =
+        fs::read_to_string(path).unwrap_or_else(|err| fatal!("Unable to read {}: {}", path, err));
+    Dataset::from_json(&json).unwrap_or_else(|err| fatal!("Unable to load {}: {}", path, err))
+}
+
 async fn root() -> &'static str {
     "Hello, World!"
 }
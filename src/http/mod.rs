@@ -1,3 +1,13 @@
+pub mod character;
+pub mod devil;
+pub mod errors;
+pub mod graph;
 pub mod handler;
+pub mod report;
 
+pub use character::*;
+pub use devil::*;
+pub use errors::*;
+pub use graph::*;
 pub use handler::*;
+pub use report::*;
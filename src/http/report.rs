@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Query},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::{
+    models::ScrapeReport,
+    services::{CharacterService, DevilService},
+};
+
+use super::ApiError;
+
+/// Entity a scrape report was written for, the devils and the characters are scraped apart
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportEntity {
+    #[default]
+    Devils,
+    Characters,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportParams {
+    #[serde(default)]
+    pub entity: ReportEntity,
+}
+
+pub async fn get_latest_report(
+    Extension(devil_service): Extension<Arc<DevilService>>,
+    Extension(character_service): Extension<Arc<CharacterService>>,
+    Query(params): Query<ReportParams>,
+) -> Result<Json<ScrapeReport>, ApiError> {
+    let report = match params.entity {
+        ReportEntity::Devils => devil_service.get_latest_report().await?,
+        ReportEntity::Characters => character_service.get_latest_report().await?,
+    };
+
+    match report {
+        Some(report) => Ok(Json(report)),
+        None => Err(ApiError::NotFound("Scrape report".to_string())),
+    }
+}
@@ -1,5 +1,14 @@
+macro_rules! fatal {
+    ($($tt:tt)*) => {{
+        use std::io::Write;
+        writeln!(&mut ::std::io::stderr(), $($tt)*).unwrap();
+        ::std::process::exit(1)
+    }}
+}
+
 pub mod cli;
 pub mod config;
+pub mod database;
 pub mod graphql;
 pub mod http;
 pub mod models;
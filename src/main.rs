@@ -13,7 +13,7 @@ struct Cli {
 #[derive(Debug, Subcommand)]
 enum Command {
     Api,
-    Scraper,
+    Scraper(Box<cli::ScraperArgs>),
 }
 
 #[tokio::main(flavor = "current_thread")]
@@ -24,6 +24,6 @@ async fn main() {
 
     match &cli.command {
         Command::Api => http::handler::run().await,
-        Command::Scraper => cli::handler::run().await,
+        Command::Scraper(args) => cli::handler::run(args).await,
     }
 }
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{AbilitySection, DevilImage, DevilName, EntityLink, Gender, ParseWarning, Status};
+
+/**
+* Humans and other characters that aren't devils, e.g. Devil Hunters and
+* members of Public Safety
+* */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Character {
+    pub id: String,
+    pub name: String,
+    pub alias_name: Option<String>,
+    pub wiki_url: String,
+    pub category: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterDetail {
+    pub id: String,
+    pub name: String,
+    pub alias_name: Option<String>,
+    pub wiki_url: String,
+    pub category: String,
+    pub names: HashMap<String, DevilName>,
+    pub image_src: Option<String>,
+    pub images: Vec<DevilImage>,
+    pub gender: Option<Gender>,
+    pub birthplace: Option<String>,
+    pub status: Option<Status>,
+    pub occupations: Vec<String>,
+    pub affiliations: Vec<String>,
+    pub contracts: Vec<EntityLink>,
+    pub relatives: Vec<EntityLink>,
+    #[serde(deserialize_with = "super::deserialize_ability_sections")]
+    pub abilities: Vec<AbilitySection>,
+    pub revision_id: Option<u64>,
+    pub revision_timestamp: Option<DateTime<Utc>>,
+    /// Fields of the infobox that couldn't be parsed
+    #[serde(default)]
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl From<&CharacterDetail> for Character {
+    fn from(detail: &CharacterDetail) -> Self {
+        Character {
+            id: detail.id.clone(),
+            name: detail.name.clone(),
+            alias_name: detail.alias_name.clone(),
+            wiki_url: detail.wiki_url.clone(),
+            category: detail.category.clone(),
+        }
+    }
+}
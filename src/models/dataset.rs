@@ -0,0 +1,104 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Version of the dataset format written by the scraper, bumped whenever the shape of the
+/// entries changes in a way older readers can't load
+pub const DATASET_SCHEMA_VERSION: u32 = 2;
+
+/// Oldest version of the dataset format still read, the abilities of version 1 are keyed by
+/// their kind and are read into sections like the ones of the current version
+pub const OLDEST_DATASET_SCHEMA_VERSION: u32 = 1;
+
+/**
+* Top-level envelope of a dataset file written by the scraper, the entries are the
+* scraped devil or character details
+* */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dataset<T> {
+    pub schema_version: u32,
+    pub scraped_at: DateTime<Utc>,
+    pub source_url: String,
+    pub entries: Vec<T>,
+}
+
+#[derive(Debug)]
+pub enum DatasetError {
+    /// A bare array of entries written before the envelope existed
+    Unversioned,
+    UnsupportedVersion(u32),
+    Json(serde_json::Error),
+}
+
+impl<T> Dataset<T> {
+    pub fn new(scraped_at: DateTime<Utc>, source_url: &str, entries: Vec<T>) -> Self {
+        Self {
+            schema_version: DATASET_SCHEMA_VERSION,
+            scraped_at,
+            source_url: source_url.to_string(),
+            entries,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Dataset<T> {
+    /// Parses a dataset file, the schema version is checked before the entries so that a file
+    /// written by another version is rejected with a clear error instead of a missing field. A
+    /// dataset of an older supported version is migrated to the current one
+    pub fn from_json(json: &str) -> Result<Self, DatasetError> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(DatasetError::Json)?;
+
+        let version = match &value {
+            serde_json::Value::Array(_) => return Err(DatasetError::Unversioned),
+            serde_json::Value::Object(object) => match object.get("schema_version") {
+                Some(version) => version
+                    .as_u64()
+                    .ok_or(DatasetError::UnsupportedVersion(0))?,
+                None => return Err(DatasetError::Unversioned),
+            },
+            _ => return Err(DatasetError::Unversioned),
+        };
+        if !(OLDEST_DATASET_SCHEMA_VERSION as u64..=DATASET_SCHEMA_VERSION as u64)
+            .contains(&version)
+        {
+            return Err(DatasetError::UnsupportedVersion(
+                u32::try_from(version).unwrap_or(u32::MAX),
+            ));
+        }
+
+        let mut dataset: Self = serde_json::from_value(value).map_err(DatasetError::Json)?;
+        dataset.schema_version = DATASET_SCHEMA_VERSION;
+
+        Ok(dataset)
+    }
+}
+
+impl fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DatasetError::Unversioned => write!(
+                f,
+                "The dataset has no schema version, it was written by an older scraper and has \
+                 to be scraped again"
+            ),
+            DatasetError::UnsupportedVersion(version)
+                if version < OLDEST_DATASET_SCHEMA_VERSION =>
+            {
+                write!(
+                    f,
+                    "The dataset has the schema version {} but version {} is expected, it was \
+                     written by an older scraper and has to be scraped again",
+                    version, DATASET_SCHEMA_VERSION
+                )
+            }
+            DatasetError::UnsupportedVersion(version) => write!(
+                f,
+                "The dataset has the schema version {} but version {} is expected, it was \
+                 written by a newer scraper",
+                version, DATASET_SCHEMA_VERSION
+            ),
+            DatasetError::Json(ref err) => write!(f, "The dataset is invalid: {}", err),
+        }
+    }
+}
@@ -1,39 +1,390 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
-#[derive(Debug, Clone)]
+use chrono::{DateTime, Utc};
+use duplicate::duplicate_item;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{CharacterDetail, ParseWarning};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Devil {
+    pub id: String,
     pub devil_name: String,
     pub alias_name: Option<String>,
     pub wiki_url: String,
-    pub category: String,
+    pub category: DevilCategory,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DevilDetail {
+    pub id: String,
+    pub devil_name: String,
+    pub alias_name: Option<String>,
+    pub wiki_url: String,
+    pub category: DevilCategory,
     pub names: HashMap<String, DevilName>,
     pub image_src: Option<String>,
-    pub gender: Option<String>,
+    pub images: Vec<DevilImage>,
+    pub gender: Option<Gender>,
     pub birthplace: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<Status>,
     pub occupations: Vec<String>,
     pub affiliations: Vec<String>,
-    pub contracts: Vec<String>,
-    pub relatives: Vec<String>,
-    pub abilities: HashMap<String, Vec<Ability>>,
+    pub contracts: Vec<EntityLink>,
+    pub relatives: Vec<EntityLink>,
+    #[serde(deserialize_with = "deserialize_ability_sections")]
+    pub abilities: Vec<AbilitySection>,
+    pub revision_id: Option<u64>,
+    pub revision_timestamp: Option<DateTime<Utc>>,
+    /// Fields of the infobox that couldn't be parsed
+    #[serde(default)]
+    pub warnings: Vec<ParseWarning>,
 }
 
 /**
-* Names will be stored as a hashmap, where the key is the language code
+* Category of a devil, from the gallery of the devils page it is listed in. It is
+* serialized as its lowercase name, e.g. `primal`, or as is when it isn't a known one
 * */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum DevilCategory {
+    Normal,
+    Primal,
+    Reincarnated,
+    Fiend,
+    Hybrid,
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Status {
+    Alive,
+    Deceased,
+    Unknown,
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Gender {
+    Male,
+    Female,
+    Other(String),
+}
+
+/**
+* Names will be stored as a hashmap, where the key is the language code. The readings
+* are the furigana written above the kanji of a name, only kanji names have them
+* */
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DevilName {
     pub devil_name: String,
     pub alias_name: Option<String>,
+    #[serde(default)]
+    pub reading: Option<String>,
+    #[serde(default)]
+    pub alias_reading: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+/**
+* Images shown in the infobox, the label is the tab title (e.g. Manga or Anime)
+* when the infobox has tabbed alternate images
+* */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevilImage {
+    pub src: String,
+    pub label: Option<String>,
+    pub caption: Option<String>,
+}
+
+/**
+* Link to another entity found in the infobox, e.g. a contracted human or a relative.
+* The qualifier is the text in parentheses next to the link (e.g. sister), the entity id
+* is only set when the linked page is in the dataset
+* */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityLink {
+    pub name: String,
+    pub wiki_url: Option<String>,
+    pub entity_id: Option<String>,
+    pub qualifier: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ability {
     pub name: String,
     pub description: String,
     pub abilities: Vec<Ability>,
 }
+
+/**
+* Abilities listed under a heading of the Abilities section of a page, in the order of
+* the page. The key of a known heading is canonical, e.g. `devil` for Devil Powers
+* */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbilitySection {
+    pub key: String,
+    pub title: String,
+    pub abilities: Vec<Ability>,
+}
+
+/**
+* Revision of a wiki page, a page whose revision id didn't change since the last
+* scrape doesn't need to be scraped again
+* */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    pub id: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Contracts and relatives of a stored entity, the part of its detail the relationship graph
+/// is built from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntityLinks {
+    pub contracts: Vec<EntityLink>,
+    pub relatives: Vec<EntityLink>,
+}
+
+/**
+* Ids of the devils and characters keyed by the url of their wiki page, the links
+* between them are resolved against it. A page listed both as a devil and as a
+* character resolves to the devil
+* */
+#[derive(Debug, Clone, Default)]
+pub struct LinkTargets {
+    ids: HashMap<String, String>,
+}
+
+impl From<&DevilDetail> for Devil {
+    fn from(detail: &DevilDetail) -> Self {
+        Devil {
+            id: detail.id.clone(),
+            devil_name: detail.devil_name.clone(),
+            alias_name: detail.alias_name.clone(),
+            wiki_url: detail.wiki_url.clone(),
+            category: detail.category.clone(),
+        }
+    }
+}
+
+impl LinkTargets {
+    /// Adds the page of an entity, the devils are added before the characters
+    pub fn insert(&mut self, wiki_url: &str, id: &str) {
+        self.ids
+            .entry(wiki_url.to_string())
+            .or_insert_with(|| id.to_string());
+    }
+
+    /// Sets the entity id of the links to a known page and clears it on the others
+    pub fn resolve(&self, links: &mut [EntityLink]) {
+        for link in links {
+            link.entity_id = link
+                .wiki_url
+                .as_ref()
+                .and_then(|wiki_url| self.ids.get(wiki_url))
+                .cloned();
+        }
+    }
+}
+
+impl AbilitySection {
+    pub fn new(title: &str, abilities: Vec<Ability>) -> Self {
+        AbilitySection {
+            key: AbilitySection::key_of(title),
+            title: title.trim().to_string(),
+            abilities,
+        }
+    }
+
+    /// Canonical key of an ability heading, the key of an unknown heading is its title in
+    /// snake case, e.g. `chainsaw_techniques`
+    pub fn key_of(title: &str) -> String {
+        match AbilitySection::known_key(title) {
+            Some(key) => key.to_string(),
+            None => heading_words(title).join("_"),
+        }
+    }
+
+    /// Canonical key of a heading the wiki lists abilities under, e.g. `devil` for Devil Powers
+    pub fn known_key(title: &str) -> Option<&'static str> {
+        // key: Lowercase heading, value: canonical key
+        const KNOWN_HEADINGS: [(&str, &str); 11] = [
+            ("physical abilities", "physical"),
+            ("physical prowess", "physical_prowess"),
+            ("devil powers", "devil"),
+            ("devil abilities", "devil"),
+            ("supernatural abilities", "supernatural"),
+            ("supernatural powers", "supernatural"),
+            ("weapons", "weapons"),
+            ("weaponry", "weapons"),
+            ("techniques", "techniques"),
+            ("hybrid transformation", "transformation"),
+            ("hybrid form", "transformation"),
+        ];
+
+        let heading = heading_words(title).join(" ");
+        KNOWN_HEADINGS
+            .iter()
+            .find(|(known, _)| *known == heading)
+            .map(|(_, key)| *key)
+    }
+}
+
+#[duplicate_item(Detail; [DevilDetail]; [CharacterDetail])]
+impl Detail {
+    /// Abilities of the section with the key, e.g. `physical`
+    pub fn abilities_of(&self, key: &str) -> Option<&[Ability]> {
+        self.abilities
+            .iter()
+            .find(|section| section.key == key)
+            .map(|section| section.abilities.as_slice())
+    }
+}
+
+impl DevilCategory {
+    /// Normalizes the name of a category, e.g. `Primal Devils` and `primal` are both primal
+    pub fn parse(text: &str) -> Self {
+        match first_word(text).trim_end_matches('s') {
+            "normal" => DevilCategory::Normal,
+            "primal" => DevilCategory::Primal,
+            "reincarnated" => DevilCategory::Reincarnated,
+            "fiend" => DevilCategory::Fiend,
+            "hybrid" => DevilCategory::Hybrid,
+            _ => DevilCategory::Other(text.trim().to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            DevilCategory::Normal => "normal",
+            DevilCategory::Primal => "primal",
+            DevilCategory::Reincarnated => "reincarnated",
+            DevilCategory::Fiend => "fiend",
+            DevilCategory::Hybrid => "hybrid",
+            DevilCategory::Other(text) => text,
+        }
+    }
+}
+
+impl Status {
+    /// Normalizes the status of an infobox, only its first word is considered so that
+    /// e.g. `Deceased (reincarnated)` is deceased
+    pub fn parse(text: &str) -> Self {
+        match first_word(text).as_str() {
+            "alive" => Status::Alive,
+            "deceased" | "dead" => Status::Deceased,
+            "unknown" => Status::Unknown,
+            _ => Status::Other(text.trim().to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Status::Alive => "alive",
+            Status::Deceased => "deceased",
+            Status::Unknown => "unknown",
+            Status::Other(text) => text,
+        }
+    }
+}
+
+impl Gender {
+    pub fn parse(text: &str) -> Self {
+        match first_word(text).as_str() {
+            "male" => Gender::Male,
+            "female" => Gender::Female,
+            _ => Gender::Other(text.trim().to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Gender::Male => "male",
+            Gender::Female => "female",
+            Gender::Other(text) => text,
+        }
+    }
+}
+
+#[duplicate_item(Kind; [DevilCategory]; [Status]; [Gender])]
+impl Kind {
+    /// Text of the kind when it isn't a known one
+    pub fn other(&self) -> Option<&str> {
+        match self {
+            Kind::Other(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+#[duplicate_item(Kind; [DevilCategory]; [Status]; [Gender])]
+impl From<String> for Kind {
+    fn from(text: String) -> Self {
+        Kind::parse(&text)
+    }
+}
+
+#[duplicate_item(Kind; [DevilCategory]; [Status]; [Gender])]
+impl From<Kind> for String {
+    fn from(kind: Kind) -> Self {
+        kind.as_str().to_string()
+    }
+}
+
+#[duplicate_item(Kind; [DevilCategory]; [Status]; [Gender])]
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Abilities are either a list of sections or, as they were stored before the sections kept
+/// their title and order, a map of the abilities keyed by their kind
+pub(super) fn deserialize_ability_sections<'de, D>(
+    deserializer: D,
+) -> Result<Vec<AbilitySection>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Abilities {
+        Sections(Vec<AbilitySection>),
+        Kinds(HashMap<String, Vec<Ability>>),
+    }
+
+    Ok(match Abilities::deserialize(deserializer)? {
+        Abilities::Sections(sections) => sections,
+        Abilities::Kinds(kinds) => {
+            let mut sections = kinds
+                .into_iter()
+                .map(|(key, abilities)| AbilitySection {
+                    title: key.clone(),
+                    key,
+                    abilities,
+                })
+                .collect::<Vec<AbilitySection>>();
+            sections.sort_by(|a, b| a.key.cmp(&b.key));
+            sections
+        }
+    })
+}
+
+fn heading_words(title: &str) -> Vec<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Lowercase first word of the text, e.g. `deceased` for `Deceased[1]`
+fn first_word(text: &str) -> String {
+    text.trim_start_matches(|c: char| !c.is_alphabetic())
+        .split(|c: char| !c.is_alphabetic())
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
@@ -0,0 +1,357 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt::Write,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Devil,
+    Character,
+}
+
+/**
+* Kind of the relationship an edge comes from, a contract links a devil to its
+* contracted human and a relative links an entity to one of its relatives
+* */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    Contract,
+    Relative,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    pub kind: NodeKind,
+    pub wiki_url: String,
+}
+
+/**
+* Edges point from the entity whose infobox has the link to the linked entity,
+* traversals follow them both ways
+* */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+    pub qualifier: Option<String>,
+}
+
+/**
+* Relationship graph of the devils and characters of the dataset, only the links
+* to a page in the dataset are edges
+* */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Neighbor {
+    pub node: GraphNode,
+    pub edge: GraphEdge,
+}
+
+/**
+* Path between two entities, `edges[i]` links `nodes[i]` and `nodes[i + 1]`
+* */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphPath {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl NodeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Devil => "devil",
+            NodeKind::Character => "character",
+        }
+    }
+}
+
+impl EdgeKind {
+    pub const ALL: [EdgeKind; 2] = [EdgeKind::Contract, EdgeKind::Relative];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeKind::Contract => "contract",
+            EdgeKind::Relative => "relative",
+        }
+    }
+}
+
+impl FromStr for EdgeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EdgeKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown edge kind {}", s))
+    }
+}
+
+impl Graph {
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Entities linked to the given one by an edge of one of the kinds, every kind is
+    /// followed when `kinds` is empty. `None` if the entity isn't in the graph
+    pub fn neighbors(&self, id: &str, kinds: &[EdgeKind]) -> Option<Vec<Neighbor>> {
+        self.node(id)?;
+
+        let nodes = self.nodes_by_id();
+        let neighbors = self
+            .adjacency(kinds)
+            .remove(id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(neighbor, edge)| Neighbor {
+                node: nodes[neighbor].clone(),
+                edge: edge.clone(),
+            })
+            .collect();
+
+        Some(neighbors)
+    }
+
+    /// Shortest path between two entities following the edges of the kinds, `None` if the
+    /// entities aren't connected
+    pub fn shortest_path(&self, from: &str, to: &str, kinds: &[EdgeKind]) -> Option<GraphPath> {
+        let nodes = self.nodes_by_id();
+        let from = nodes.get(from)?;
+        nodes.get(to)?;
+
+        let adjacency = self.adjacency(kinds);
+
+        // key: id of a visited node, value: the node and edge it was reached from
+        let mut previous: HashMap<&str, Option<(&str, &GraphEdge)>> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        previous.insert(&from.id, None);
+        queue.push_back(&from.id);
+
+        while let Some(id) = queue.pop_front() {
+            if id == to {
+                break;
+            }
+
+            for (neighbor, edge) in adjacency.get(id).into_iter().flatten() {
+                if !previous.contains_key(neighbor) {
+                    previous.insert(neighbor, Some((id, edge)));
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        let mut path = GraphPath {
+            nodes: vec![nodes[to].clone()],
+            edges: Vec::new(),
+        };
+        let mut id = to;
+        while let Some((from, edge)) = previous.get(id)? {
+            path.nodes.push(nodes[from].clone());
+            path.edges.push((*edge).clone());
+            id = from;
+        }
+        path.nodes.reverse();
+        path.edges.reverse();
+
+        Some(path)
+    }
+
+    /// Connected components of the graph when only the edges of the kinds are kept, from the
+    /// largest to the smallest. The entities without any of these edges are left out
+    pub fn components(&self, kinds: &[EdgeKind]) -> Vec<Graph> {
+        let adjacency = self.adjacency(kinds);
+
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut components: Vec<Graph> = Vec::new();
+        for node in &self.nodes {
+            if visited.contains(node.id.as_str()) || !adjacency.contains_key(node.id.as_str()) {
+                continue;
+            }
+
+            let mut members: HashSet<&str> = HashSet::new();
+            let mut stack = vec![node.id.as_str()];
+            while let Some(id) = stack.pop() {
+                if !visited.insert(id) {
+                    continue;
+                }
+                members.insert(id);
+                for (neighbor, _) in adjacency.get(id).into_iter().flatten() {
+                    stack.push(neighbor);
+                }
+            }
+
+            components.push(Graph {
+                nodes: self
+                    .nodes
+                    .iter()
+                    .filter(|node| members.contains(node.id.as_str()))
+                    .cloned()
+                    .collect(),
+                edges: self
+                    .edges
+                    .iter()
+                    .filter(|edge| {
+                        is_followed(edge, kinds) && members.contains(edge.source.as_str())
+                    })
+                    .cloned()
+                    .collect(),
+            });
+        }
+
+        components.sort_by_key(|component| Reverse(component.nodes.len()));
+        components
+    }
+
+    /// The graph in the GraphML format
+    pub fn to_graphml(&self) -> String {
+        let mut graphml = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "\n",
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#,
+            "\n",
+            r#"  <key id="name" for="node" attr.name="name" attr.type="string"/>"#,
+            "\n",
+            r#"  <key id="kind" for="node" attr.name="kind" attr.type="string"/>"#,
+            "\n",
+            r#"  <key id="wiki_url" for="node" attr.name="wiki_url" attr.type="string"/>"#,
+            "\n",
+            r#"  <key id="edge_kind" for="edge" attr.name="kind" attr.type="string"/>"#,
+            "\n",
+            r#"  <key id="qualifier" for="edge" attr.name="qualifier" attr.type="string"/>"#,
+            "\n",
+            r#"  <graph id="chainsawman" edgedefault="directed">"#,
+            "\n",
+        ));
+
+        for node in &self.nodes {
+            let _ = writeln!(
+                graphml,
+                r#"    <node id="{}"><data key="name">{}</data><data key="kind">{}</data><data key="wiki_url">{}</data></node>"#,
+                escape_xml(&node.id),
+                escape_xml(&node.name),
+                node.kind.as_str(),
+                escape_xml(&node.wiki_url)
+            );
+        }
+        for edge in &self.edges {
+            let qualifier = match &edge.qualifier {
+                Some(qualifier) => {
+                    format!(r#"<data key="qualifier">{}</data>"#, escape_xml(qualifier))
+                }
+                None => String::new(),
+            };
+            let _ = writeln!(
+                graphml,
+                r#"    <edge source="{}" target="{}"><data key="edge_kind">{}</data>{}</edge>"#,
+                escape_xml(&edge.source),
+                escape_xml(&edge.target),
+                edge.kind.as_str(),
+                qualifier
+            );
+        }
+
+        graphml.push_str("  </graph>\n</graphml>\n");
+        graphml
+    }
+
+    /// The graph in the DOT language of Graphviz
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph chainsawman {\n");
+
+        for node in &self.nodes {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\", kind=\"{}\", URL=\"{}\"];",
+                escape_dot(&node.id),
+                escape_dot(&node.name),
+                node.kind.as_str(),
+                escape_dot(&node.wiki_url)
+            );
+        }
+        for edge in &self.edges {
+            let label = match &edge.qualifier {
+                Some(qualifier) => format!("{} ({})", edge.kind.as_str(), qualifier),
+                None => edge.kind.as_str().to_string(),
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}\", kind=\"{}\"];",
+                escape_dot(&edge.source),
+                escape_dot(&edge.target),
+                escape_dot(&label),
+                edge.kind.as_str()
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn nodes_by_id(&self) -> HashMap<&str, &GraphNode> {
+        self.nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect()
+    }
+
+    /// Neighbors of every node through the edges of the kinds, in both directions. The
+    /// neighbors are ordered by id so that traversals are deterministic
+    fn adjacency(&self, kinds: &[EdgeKind]) -> HashMap<&str, Vec<(&str, &GraphEdge)>> {
+        let mut adjacency: HashMap<&str, BTreeMap<(&str, usize), &GraphEdge>> = HashMap::new();
+        for (position, edge) in self.edges.iter().enumerate() {
+            if !is_followed(edge, kinds) {
+                continue;
+            }
+
+            adjacency
+                .entry(&edge.source)
+                .or_default()
+                .insert((&edge.target, position), edge);
+            adjacency
+                .entry(&edge.target)
+                .or_default()
+                .insert((&edge.source, position), edge);
+        }
+
+        adjacency
+            .into_iter()
+            .map(|(id, neighbors)| {
+                let neighbors = neighbors
+                    .into_iter()
+                    .map(|((neighbor, _), edge)| (neighbor, edge))
+                    .collect();
+                (id, neighbors)
+            })
+            .collect()
+    }
+}
+
+fn is_followed(edge: &GraphEdge, kinds: &[EdgeKind]) -> bool {
+    kinds.is_empty() || kinds.contains(&edge.kind)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
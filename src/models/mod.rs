@@ -1,3 +1,11 @@
+pub mod character;
+pub mod dataset;
 pub mod devil;
+pub mod graph;
+pub mod report;
 
+pub use character::*;
+pub use dataset::*;
 pub use devil::*;
+pub use graph::*;
+pub use report::*;
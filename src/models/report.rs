@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::DevilDetail;
+
+#[derive(Debug, Clone)]
+pub struct ScrapeResult<D = DevilDetail> {
+    pub details: Vec<D>,
+    pub report: ScrapeReport,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrapeReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub succeeded: Vec<ScrapedPage>,
+    pub failed: Vec<FailedPage>,
+    /// Ids of the pages that didn't change since the last scrape
+    #[serde(default)]
+    pub unchanged: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrapedPage {
+    pub id: String,
+    pub wiki_url: String,
+    pub category: String,
+    pub duration_ms: u64,
+    /// Fields of the page that couldn't be parsed without failing the scrape
+    #[serde(default)]
+    pub warnings: Vec<ParseWarning>,
+}
+
+/**
+* The error kind tells apart a wiki layout change (e.g. `missing_section`) from
+* a transient failure (e.g. `network`)
+* */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedPage {
+    pub id: String,
+    pub wiki_url: String,
+    pub category: String,
+    pub error_kind: String,
+    pub error: String,
+    pub duration_ms: u64,
+}
+
+/**
+* Field of an infobox that is missing or couldn't be parsed, the rest of the page is
+* still scraped (e.g. a Name section without any italic romaji)
+* */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseWarning {
+    pub field: String,
+    pub message: String,
+}
+
+impl ScrapeReport {
+    pub fn duration_ms(&self) -> i64 {
+        (self.finished_at - self.started_at).num_milliseconds()
+    }
+}
+
+impl ParseWarning {
+    pub fn new(field: &str, message: &str) -> Self {
+        ParseWarning {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
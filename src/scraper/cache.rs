@@ -0,0 +1,68 @@
+use std::{
+    io::{Error, ErrorKind},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const DEFAULT_CACHE_DIR: &str = ".cache/wiki";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Serves cached responses after revalidating them with the wiki
+    Revalidate,
+    /// Ignores cached responses and replaces them with fresh ones
+    Refresh,
+    /// Only serves cached responses without making any request
+    Offline,
+}
+
+/// Response body stored together with the validators needed to revalidate it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+/// Stores responses on disk as `<dir>/<sha256 of the url>.json`
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        ResponseCache { dir: dir.into() }
+    }
+
+    fn path_of(&self, url: &str) -> PathBuf {
+        let hash = Sha256::digest(url.as_bytes());
+        self.dir.join(format!("{:x}.json", hash))
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Option<CachedResponse>, Error> {
+        let content = match tokio::fs::read(self.path_of(url)).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let cached: CachedResponse = serde_json::from_slice(&content)?;
+
+        // Guards against the (unlikely) case of two urls sharing a hash
+        if cached.url != url {
+            return Ok(None);
+        }
+
+        Ok(Some(cached))
+    }
+
+    pub async fn put(&self, response: &CachedResponse) -> Result<(), Error> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let content = serde_json::to_vec(response)?;
+        tokio::fs::write(self.path_of(&response.url), content).await
+    }
+}
@@ -0,0 +1,125 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use scraper::Html;
+
+use crate::{
+    models::{Character, CharacterDetail, Revision, ScrapeResult},
+    services::CharacterDataSource,
+};
+
+use super::{
+    devil::{
+        devil_id_from_url, scrape_details, scrape_gallery_links, scrape_infobox,
+        scrape_page_abilities, CHAINSAWMAN_WIKI_BASE_URL,
+    },
+    mediawiki::fetch_revisions,
+    DevilScraperConfig, Fetcher, ScrapeError, ScraperSelectors,
+};
+
+/// Scrapes the humans and other characters that aren't devils from the rendered wiki pages
+pub struct CharacterScraper {
+    config: DevilScraperConfig,
+    fetcher: Arc<dyn Fetcher>,
+}
+
+impl CharacterScraper {
+    pub fn new(config: DevilScraperConfig, fetcher: Arc<dyn Fetcher>) -> Self {
+        CharacterScraper { config, fetcher }
+    }
+}
+
+#[async_trait]
+impl CharacterDataSource for CharacterScraper {
+    async fn scrape_character_details(
+        &self,
+        characters: &[Character],
+    ) -> Result<ScrapeResult<CharacterDetail>, ScrapeError> {
+        let fetcher = self.fetcher.clone();
+        let selectors = self.config.selectors.clone();
+
+        Ok(
+            scrape_details(self.config.num_of_workers, characters, move |character| {
+                let fetcher = fetcher.clone();
+                let selectors = selectors.clone();
+                async move {
+                    scrape_character_detail(fetcher.as_ref(), &selectors, &character).await
+                }
+            })
+            .await,
+        )
+    }
+
+    async fn scrape_characters(&self) -> Result<Vec<Character>, ScrapeError> {
+        scrape_characters(self.fetcher.as_ref(), &self.config.selectors).await
+    }
+
+    async fn scrape_character_detail(
+        &self,
+        character: &Character,
+    ) -> Result<CharacterDetail, ScrapeError> {
+        scrape_character_detail(self.fetcher.as_ref(), &self.config.selectors, character).await
+    }
+
+    async fn scrape_character_revisions(
+        &self,
+        characters: &[Character],
+    ) -> Result<HashMap<String, Revision>, ScrapeError> {
+        fetch_revisions(self.fetcher.as_ref(), characters).await
+    }
+}
+
+async fn scrape_characters(
+    fetcher: &dyn Fetcher,
+    selectors: &ScraperSelectors,
+) -> Result<Vec<Character>, ScrapeError> {
+    let galleries = &selectors.galleries;
+
+    let characters_page = format!("{}/wiki/Characters", CHAINSAWMAN_WIKI_BASE_URL);
+    let links =
+        scrape_gallery_links(fetcher, &characters_page, &galleries.characters, galleries).await?;
+
+    Ok(links
+        .into_iter()
+        .map(|link| Character {
+            id: devil_id_from_url(&link.wiki_url),
+            name: link.name,
+            alias_name: link.alias_name,
+            wiki_url: link.wiki_url,
+            category: link.category,
+        })
+        .collect())
+}
+
+async fn scrape_character_detail(
+    fetcher: &dyn Fetcher,
+    selectors: &ScraperSelectors,
+    character: &Character,
+) -> Result<CharacterDetail, ScrapeError> {
+    let html = fetcher.fetch(&character.wiki_url).await?;
+    let document = Html::parse_document(&html);
+
+    let infobox = scrape_infobox(&document, selectors);
+
+    Ok(CharacterDetail {
+        id: character.id.clone(),
+        name: character.name.clone(),
+        alias_name: character.alias_name.clone(),
+        wiki_url: character.wiki_url.clone(),
+        category: character.category.clone(),
+        names: infobox.names,
+        image_src: infobox.images.first().map(|image| image.src.clone()),
+        images: infobox.images,
+        gender: infobox.gender,
+        birthplace: infobox.birthplace,
+        status: infobox.status,
+        occupations: infobox.occupations,
+        affiliations: infobox.affiliations,
+        contracts: infobox.contracts,
+        relatives: infobox.relatives,
+        abilities: scrape_page_abilities(&document, &selectors.abilities),
+        revision_id: None,
+        revision_timestamp: None,
+        warnings: infobox.warnings,
+    })
+}
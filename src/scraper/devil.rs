@@ -1,84 +1,284 @@
-use std::{
-    collections::HashMap,
-    io::{Error, ErrorKind},
-    sync::Arc,
-    time::Duration,
-};
+use std::{collections::HashMap, future::Future, sync::Arc, time::Instant};
 
 use async_trait::async_trait;
+use chrono::Utc;
 use duplicate::duplicate_item;
 use lazy_static::lazy_static;
+use percent_encoding::percent_decode_str;
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
-use tokio::{
-    sync::{Mutex, Semaphore},
-    task::JoinHandle,
-};
+use tokio::{sync::Semaphore, task::JoinHandle};
 
 use crate::{
-    models::devil::{Ability, Devil, DevilDetail, DevilName},
+    models::{
+        Ability, AbilitySection, Character, CharacterDetail, Devil, DevilCategory, DevilDetail,
+        DevilImage, DevilName, EntityLink, FailedPage, Gender, ParseWarning, Revision,
+        ScrapeReport, ScrapeResult, ScrapedPage, Status,
+    },
     services::DevilDataSource,
 };
 
-const CHAINSAWMAN_WIKI_BASE_URL: &'static str = "https://chainsaw-man.fandom.com";
+use super::{
+    mediawiki::fetch_revisions,
+    selectors::{
+        AbilitySelectors, Gallery, GallerySelectors, ImageSelectors, InfoboxField, InfoboxSelectors,
+    },
+    Fetcher, ScrapeError, ScraperSelectors,
+};
+
+pub const CHAINSAWMAN_WIKI_BASE_URL: &str = "https://chainsaw-man.fandom.com";
 
-const SECTION_NAME: &'static str = "Name";
-const SECTION_BIOLOGICAL: &'static str = "Biological Information";
-const SECTION_PROFESSIONAL: &'static str = "Professional Information";
+pub const NUM_OF_SCRAPER_WORKERS: usize = 5;
 
-const NUM_OF_SCRAPER_WORKERS: usize = 5;
-const TASK_FINISH_DELAY_MS: u64 = 3000;
+/// Requests are throttled by the fetcher, the workers only bound how many devil
+/// pages are being scraped at the same time
+#[derive(Debug, Clone)]
+pub struct DevilScraperConfig {
+    pub num_of_workers: usize,
+    /// Selectors of the rendered pages, only read by the html scrapers
+    pub selectors: Arc<ScraperSelectors>,
+}
 
-pub struct DevilScraper {}
+impl Default for DevilScraperConfig {
+    fn default() -> Self {
+        DevilScraperConfig {
+            num_of_workers: NUM_OF_SCRAPER_WORKERS,
+            selectors: ScraperSelectors::builtin(),
+        }
+    }
+}
+
+pub struct DevilScraper {
+    config: DevilScraperConfig,
+    fetcher: Arc<dyn Fetcher>,
+}
 
 impl DevilScraper {
-    pub fn new() -> Self {
-        DevilScraper {}
+    pub fn new(config: DevilScraperConfig, fetcher: Arc<dyn Fetcher>) -> Self {
+        DevilScraper { config, fetcher }
     }
 }
 
 #[duplicate_item(Interface; [DevilDataSource])]
 #[async_trait]
 impl Interface for DevilScraper {
-    async fn scrape(&self) -> Result<Vec<DevilDetail>, std::io::Error> {
-        let devils = match scrape_devils().await {
-            Ok(devils) => devils,
-            Err(e) => panic!("{}", e.to_string()),
-        };
+    async fn scrape(&self, devils: &[Devil]) -> Result<ScrapeResult, ScrapeError> {
+        let fetcher = self.fetcher.clone();
+        let selectors = self.config.selectors.clone();
+
+        Ok(
+            scrape_details(self.config.num_of_workers, devils, move |devil| {
+                let fetcher = fetcher.clone();
+                let selectors = selectors.clone();
+                async move { scrape_devil_detail(fetcher.as_ref(), &selectors, &devil).await }
+            })
+            .await,
+        )
+    }
+
+    async fn scrape_devils(&self) -> Result<Vec<Devil>, ScrapeError> {
+        scrape_devils(self.fetcher.as_ref(), &self.config.selectors).await
+    }
+
+    async fn scrape_devil_detail(&self, devil: &Devil) -> Result<DevilDetail, ScrapeError> {
+        scrape_devil_detail(self.fetcher.as_ref(), &self.config.selectors, devil).await
+    }
 
-        let result: Arc<Mutex<Vec<DevilDetail>>> = Arc::new(Mutex::new(Vec::new()));
+    // Rendered pages don't tell when they were last edited, the revisions come from the API
+    async fn scrape_revisions(
+        &self,
+        devils: &[Devil],
+    ) -> Result<HashMap<String, Revision>, ScrapeError> {
+        fetch_revisions(self.fetcher.as_ref(), devils).await
+    }
+}
 
-        let semaphore = Arc::new(Semaphore::new(NUM_OF_SCRAPER_WORKERS));
-        let mut join_handles: Vec<JoinHandle<()>> = Vec::new();
+/// Detail scraped from a page of the wiki
+pub(super) trait WikiDetail: Send + 'static {
+    /// Fields of the page that couldn't be parsed
+    fn warnings(&self) -> &[ParseWarning];
+}
 
-        for devil in devils {
-            let permit = semaphore.clone().acquire_owned().await.unwrap();
-            let result = result.clone();
+#[duplicate_item(detail_type; [DevilDetail]; [CharacterDetail])]
+impl WikiDetail for detail_type {
+    fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+}
 
-            join_handles.push(tokio::spawn(async move {
-                let detail = match scrape_devil_detail(devil.wiki_url.clone()).await {
-                    Ok(detail) => detail,
-                    Err(_) => return (),
-                };
+/// Page of the wiki a detail is scraped from
+pub(super) trait WikiPage: Clone + Send + 'static {
+    fn id(&self) -> &str;
+    fn wiki_url(&self) -> &str;
+    fn category(&self) -> &str;
+    /// Wraps an error that happened while scraping the page
+    fn scrape_error(&self, error: ScrapeError) -> ScrapeError;
+}
 
-                let mut guard = result.lock().await;
-                guard.push(detail);
-                std::mem::drop(guard);
+impl WikiPage for Devil {
+    fn id(&self) -> &str {
+        &self.id
+    }
 
-                tokio::time::sleep(Duration::from_millis(TASK_FINISH_DELAY_MS)).await;
-                drop(permit);
-            }));
+    fn wiki_url(&self) -> &str {
+        &self.wiki_url
+    }
+
+    fn category(&self) -> &str {
+        self.category.as_str()
+    }
+
+    fn scrape_error(&self, error: ScrapeError) -> ScrapeError {
+        ScrapeError::Page {
+            entity: "devil",
+            url: self.wiki_url.clone(),
+            error: Box::new(error),
+        }
+    }
+}
+
+impl WikiPage for Character {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn wiki_url(&self) -> &str {
+        &self.wiki_url
+    }
+
+    fn category(&self) -> &str {
+        &self.category
+    }
+
+    fn scrape_error(&self, error: ScrapeError) -> ScrapeError {
+        ScrapeError::Page {
+            entity: "character",
+            url: self.wiki_url.clone(),
+            error: Box::new(error),
         }
+    }
+}
+
+/// Task scraping the detail of a page, resolving to its result and duration in milliseconds
+type DetailTask<D> = JoinHandle<(Result<D, ScrapeError>, u64)>;
+
+/// Scrapes the details of every page with at most `num_of_workers` pages at once, a page
+/// that fails to be scraped is recorded in the report instead of failing the whole scrape
+pub(super) async fn scrape_details<P, D, F, Fut>(
+    num_of_workers: usize,
+    pages: &[P],
+    scrape_detail: F,
+) -> ScrapeResult<D>
+where
+    P: WikiPage,
+    D: WikiDetail,
+    F: Fn(P) -> Fut,
+    Fut: Future<Output = Result<D, ScrapeError>> + Send + 'static,
+{
+    let started_at = Utc::now();
+
+    let semaphore = Arc::new(Semaphore::new(num_of_workers));
+    let mut join_handles: Vec<(P, DetailTask<D>)> = Vec::new();
+
+    for page in pages {
+        // The semaphore is never closed, acquiring a permit can't fail
+        let permit = semaphore.clone().acquire_owned().await.ok();
+        let task = scrape_detail(page.clone());
+
+        join_handles.push((
+            page.clone(),
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let result = task.await;
+                let duration_ms = started.elapsed().as_millis() as u64;
+                drop(permit);
+
+                (result, duration_ms)
+            }),
+        ));
+    }
+
+    let mut details: Vec<D> = Vec::new();
+    let mut succeeded: Vec<ScrapedPage> = Vec::new();
+    let mut failed: Vec<FailedPage> = Vec::new();
+
+    for (page, handle) in join_handles {
+        // A panicking page fails on its own instead of aborting the whole scrape
+        let (result, duration_ms) = match handle.await {
+            Ok(joined) => joined,
+            Err(error) => (
+                Err(ScrapeError::Task {
+                    url: page.wiki_url().to_string(),
+                    message: error.to_string(),
+                }),
+                0,
+            ),
+        };
+        match result {
+            Ok(detail) => {
+                for warning in detail.warnings() {
+                    tracing::warn!(
+                        "Unable to parse {} of {}: {}",
+                        warning.field,
+                        page.wiki_url(),
+                        warning.message
+                    );
+                }
 
-        for handle in join_handles {
-            handle.await.unwrap();
+                succeeded.push(ScrapedPage {
+                    id: page.id().to_string(),
+                    wiki_url: page.wiki_url().to_string(),
+                    category: page.category().to_string(),
+                    duration_ms,
+                    warnings: detail.warnings().to_vec(),
+                });
+                details.push(detail);
+            }
+            Err(error) => {
+                let error = page.scrape_error(error);
+                tracing::warn!("{}", error);
+
+                failed.push(FailedPage {
+                    id: page.id().to_string(),
+                    wiki_url: page.wiki_url().to_string(),
+                    category: page.category().to_string(),
+                    error_kind: error.kind().to_string(),
+                    error: error.to_string(),
+                    duration_ms,
+                });
+            }
         }
+    }
 
-        let result = result.lock().await;
-        Ok(result.to_vec())
+    ScrapeResult {
+        details,
+        report: ScrapeReport {
+            started_at,
+            finished_at: Utc::now(),
+            succeeded,
+            failed,
+            unchanged: Vec::new(),
+        },
     }
 }
 
+/// Derives a stable devil id from its wiki url, e.g. `/wiki/Chainsaw_Devil` becomes `chainsaw-devil`
+pub fn devil_id_from_url(url: &str) -> String {
+    let path = url.rsplit("/wiki/").next().unwrap_or(url);
+    let path = percent_decode_str(path).decode_utf8_lossy();
+
+    let mut id = String::new();
+    for c in path.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            id.push(c);
+        } else if !id.is_empty() && !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+
+    id.trim_end_matches('-').to_string()
+}
+
 #[allow(dead_code)]
 fn print_ability_tree(level: i32, ability: &Ability, with_description: bool) {
     println!("level: {} - ability: {}", level, ability.name);
@@ -87,58 +287,65 @@ fn print_ability_tree(level: i32, ability: &Ability, with_description: bool) {
     }
 
     for a in &ability.abilities {
-        print_ability_tree(level + 1, &a, with_description);
-    }
-}
-
-// TODO: Improve error handling to not rely on std::io::Error
-async fn scrape_devils() -> Result<Vec<Devil>, Error> {
-    // key: Category, value: selector for the devil category div
-    let map = HashMap::<&'static str, Selector>::from([
-        (
-            "Normal Devils",
-            Selector::parse(r#"div[id="gallery-0"]"#).unwrap(),
-        ),
-        (
-            "Primal Devils",
-            Selector::parse(r#"div[id="gallery-1"]"#).unwrap(),
-        ),
-        (
-            "Reincarnated Devils",
-            Selector::parse(r#"div[id="gallery-2"]"#).unwrap(),
-        ),
-        ("Fiends", Selector::parse(r#"div[id="gallery-4"]"#).unwrap()),
-        (
-            "Hybrids",
-            Selector::parse(r#"div[id="gallery-5"]"#).unwrap(),
-        ),
-    ]);
-
-    let mut devils: Vec<Devil> = Vec::new();
+        print_ability_tree(level + 1, a, with_description);
+    }
+}
 
-    let devils_page = format!("{}/wiki/devil", CHAINSAWMAN_WIKI_BASE_URL);
-    let response = match reqwest::get(devils_page).await {
-        Ok(html) => html,
-        Err(e) => return Err(Error::new(ErrorKind::Other, e.to_string())),
-    };
+/// Link to a page found in a category gallery
+pub(super) struct GalleryLink {
+    pub wiki_url: String,
+    pub name: String,
+    pub alias_name: Option<String>,
+    pub category: String,
+}
 
-    let html = match response.text().await {
-        Ok(text) => text,
-        Err(e) => return Err(Error::new(ErrorKind::Other, e.to_string())),
-    };
+impl GalleryLink {
+    /// Splits a `Alias/Name` caption, e.g. `Nayuta/Control Devil`, into its name and alias
+    pub(super) fn new(wiki_url: String, caption: &str, category: &str) -> Self {
+        let names = caption.split('/').collect::<Vec<&str>>();
 
-    let document = Html::parse_document(&html);
-    for (category, root_selector) in map.into_iter() {
-        let children_selector = Selector::parse(r#"div[class="wikia-gallery-item"]"#).unwrap();
-
-        let root = document.select(&root_selector).next().unwrap();
-        for el in root.select(&children_selector) {
-            let link_selector = match Selector::parse(r#"div[class="lightbox-caption"] > a"#) {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
+        let mut name: String = names[0].into();
+        let mut alias_name: Option<String> = None;
+
+        if names.len() == 2 {
+            alias_name = Some(names[0].into());
+            name = names[1].into();
+        }
 
-            let a = match el.select(&link_selector).next() {
+        GalleryLink {
+            wiki_url,
+            name,
+            alias_name,
+            category: category.to_string(),
+        }
+    }
+}
+
+/// Scrapes the links of the category galleries of a page, `galleries` are the gallery divs of
+/// every category
+pub(super) async fn scrape_gallery_links(
+    fetcher: &dyn Fetcher,
+    page_url: &str,
+    galleries: &[Gallery],
+    selectors: &GallerySelectors,
+) -> Result<Vec<GalleryLink>, ScrapeError> {
+    let mut links: Vec<GalleryLink> = Vec::new();
+
+    let html = fetcher.fetch(page_url).await?;
+
+    let document = Html::parse_document(&html);
+    for gallery in galleries {
+        let root = match document.select(&gallery.root).next() {
+            Some(root) => root,
+            None => {
+                return Err(ScrapeError::MissingSection {
+                    url: page_url.to_string(),
+                    section: gallery.id.clone(),
+                })
+            }
+        };
+        for el in root.select(&selectors.item) {
+            let a = match el.select(&selectors.link).next() {
                 Some(el) => el,
                 None => continue,
             };
@@ -148,298 +355,696 @@ async fn scrape_devils() -> Result<Vec<Devil>, Error> {
             };
 
             let wiki_url = format!("{}{}", CHAINSAWMAN_WIKI_BASE_URL, href);
+            let caption = a.text().collect::<String>();
+
+            links.push(GalleryLink::new(wiki_url, &caption, &gallery.category));
+        }
+    }
 
-            let raw_name = a.text().collect::<String>();
-            let splitted_names = raw_name.split("/");
-            let names = splitted_names.collect::<Vec<&str>>();
+    Ok(links)
+}
 
-            let mut devil_name: String = names[0].into();
-            let mut alias_name: Option<String> = None;
+async fn scrape_devils(
+    fetcher: &dyn Fetcher,
+    selectors: &ScraperSelectors,
+) -> Result<Vec<Devil>, ScrapeError> {
+    let galleries = &selectors.galleries;
 
-            if names.len() == 2 {
-                alias_name = Some(names[0].into());
-                devil_name = names[1].into();
-            }
+    let devils_page = format!("{}/wiki/devil", CHAINSAWMAN_WIKI_BASE_URL);
+    let links = scrape_gallery_links(fetcher, &devils_page, &galleries.devils, galleries).await?;
 
-            devils.push(Devil {
-                devil_name,
-                alias_name,
-                wiki_url,
-                category: category.to_string(),
-            });
+    Ok(links
+        .into_iter()
+        .map(|link| Devil {
+            id: devil_id_from_url(&link.wiki_url),
+            devil_name: link.name,
+            alias_name: link.alias_name,
+            wiki_url: link.wiki_url,
+            category: DevilCategory::parse(&link.category),
+        })
+        .collect())
+}
+
+/// Abilities listed right under a heading, the figures between the lists are skipped and
+/// any other element ends them
+fn scrape_heading_abilities(heading: ElementRef) -> Vec<Ability> {
+    let mut abilities = Vec::new();
+
+    for sibling in heading.next_siblings() {
+        let el = match ElementRef::wrap(sibling) {
+            Some(el) => el,
+            None => continue,
+        };
+        match el.value().name() {
+            "ul" => abilities.extend(scrape_ability_list(el)),
+            "figure" => continue,
+            _ => break,
         }
     }
 
-    Ok(devils)
+    abilities
 }
 
-fn scrape_abilities(document: &Html, selector: Selector) -> Vec<Ability> {
-    let mut abilities = Vec::new();
+/// Abilities of the items of an ability list, the lists nested in an item are the abilities
+/// of the ability the item describes
+pub fn scrape_ability_list(list: ElementRef) -> Vec<Ability> {
+    let mut abilities: Vec<Ability> = Vec::new();
 
-    if let Some(el) = document.select(&selector).next() {
-        let parent: ElementRef = el.parent().and_then(ElementRef::wrap).unwrap();
-        for sibling in parent.next_siblings() {
-            if let Some(el) = sibling.value().as_element() {
-                if el.name() != "ul" && el.name() != "figure" {
-                    break;
-                }
+    for li in list.children().filter_map(ElementRef::wrap) {
+        if li.value().name() != "li" {
+            continue;
+        }
 
-                let ul = match ElementRef::wrap(sibling) {
-                    Some(ul) => ul,
-                    None => continue,
-                };
+        let child_abilities = li
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|child| is_list(child))
+            .flat_map(scrape_ability_list)
+            .collect::<Vec<Ability>>();
+
+        let text = own_text(li);
+        let (name, description) = match text.split_once(':') {
+            Some((name, description)) => (name.trim(), description.trim()),
+            None => (text.as_str(), ""),
+        };
 
-                let li_selector = Selector::parse(r#":scope > li"#).unwrap();
-                for li in ul.select(&li_selector) {
-                    get_abilities(li, &mut abilities);
-                }
-            }
+        // An item without text only groups the abilities of its nested lists
+        if name.is_empty() {
+            abilities.extend(child_abilities);
+            continue;
         }
+
+        abilities.push(Ability {
+            name: name.to_string(),
+            description: description.to_string(),
+            abilities: child_abilities,
+        });
     }
 
     abilities
 }
 
-fn get_abilities(element: ElementRef, abilities: &mut Vec<Ability>) {
+/// Text of an element without the text of its nested lists and references, with its
+/// whitespace collapsed
+fn own_text(element: ElementRef) -> String {
     lazy_static! {
-        static ref REF_CLEANER: Regex = Regex::new(r#"\[\d+\]/gm"#).unwrap();
+        static ref REF_CLEANER: Regex = Regex::new(r"\[\d+\]").unwrap();
     }
 
-    let texts = element.text().collect::<String>();
-    let texts = texts
-        .split(":")
+    fn collect(element: ElementRef, text: &mut String) {
+        for child in element.children() {
+            if let Some(fragment) = child.value().as_text() {
+                text.push_str(fragment);
+            } else if let Some(child) = ElementRef::wrap(child) {
+                if !is_list(&child) && !child.value().classes().any(|class| class == "reference") {
+                    collect(child, text);
+                }
+            }
+        }
+    }
+
+    let mut text = String::new();
+    collect(element, &mut text);
+
+    REF_CLEANER
+        .replace_all(&text, "")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn is_list(element: &ElementRef) -> bool {
+    matches!(element.value().name(), "ul" | "ol")
+}
+
+/// Lines of a kanji name as `(kanji, reading)`, the furigana of the `<ruby>` annotations is
+/// left out of the kanji and makes up the reading along with the text that isn't annotated
+fn scrape_kanji_lines(div: ElementRef) -> Vec<(String, Option<String>)> {
+    #[derive(Default)]
+    struct Line {
+        kanji: String,
+        reading: String,
+        has_ruby: bool,
+    }
+
+    fn collect(element: ElementRef, lines: &mut Vec<Line>) {
+        for child in element.children() {
+            let line = lines.last_mut().unwrap();
+            if let Some(text) = child.value().as_text() {
+                line.kanji.push_str(text);
+                line.reading.push_str(text);
+                continue;
+            }
+
+            let child = match ElementRef::wrap(child) {
+                Some(child) => child,
+                None => continue,
+            };
+            match child.value().name() {
+                "br" => lines.push(Line::default()),
+                "ruby" => {
+                    line.has_ruby = true;
+                    for part in child.children() {
+                        if let Some(text) = part.value().as_text() {
+                            line.kanji.push_str(text);
+                            continue;
+                        }
+                        let part = match ElementRef::wrap(part) {
+                            Some(part) => part,
+                            None => continue,
+                        };
+                        match part.value().name() {
+                            "rt" => line.reading.extend(part.text()),
+                            "rp" => {}
+                            _ => line.kanji.extend(part.text()),
+                        }
+                    }
+                }
+                "sup" => {}
+                _ => collect(child, lines),
+            }
+        }
+    }
+
+    let mut lines = vec![Line::default()];
+    collect(div, &mut lines);
+
+    lines
         .into_iter()
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
+        .map(|line| {
+            let reading = Some(line.reading.trim().to_string())
+                .filter(|reading| line.has_ruby && !reading.is_empty());
+            (line.kanji.trim().to_string(), reading)
+        })
+        .filter(|(kanji, _)| !kanji.is_empty())
+        .collect()
+}
 
-    if texts.len() < 2 {
-        return;
+fn scrape_images(document: &Html, selectors: &ImageSelectors) -> Vec<DevilImage> {
+    let mut images: Vec<DevilImage> = Vec::new();
+
+    for figure in document.select(&selectors.figure) {
+        if let Some(image) = scrape_image(figure, None, selectors) {
+            images.push(image);
+        }
     }
 
-    let name = texts[0].trim().to_string();
-    let description = REF_CLEANER
-        .replace_all(&texts[1].trim().to_string(), "")
-        .to_string();
+    // Tabbed alternate images, the n-th tab labels the n-th tab content
+    for collection in document.select(&selectors.collection) {
+        let labels = collection
+            .select(&selectors.tab_label)
+            .map(|label| label.text().collect::<String>().trim().to_string());
 
-    let mut child_abilities: Vec<Ability> = Vec::new();
-    let child_selector = Selector::parse(r#"ul > li"#).unwrap();
+        for (content, label) in collection.select(&selectors.tab_content).zip(labels) {
+            let label = if label.is_empty() { None } else { Some(label) };
 
-    for child in element.select(&child_selector) {
-        get_abilities(child, &mut child_abilities);
+            for figure in content.select(&selectors.tab_figure) {
+                if let Some(image) = scrape_image(figure, label.clone(), selectors) {
+                    images.push(image);
+                }
+            }
+        }
     }
 
-    abilities.push(Ability {
-        name,
-        description,
-        abilities: child_abilities,
-    });
+    images
 }
 
-async fn scrape_devil_detail(url: String) -> Result<DevilDetail, Error> {
-    lazy_static! {
-        // <a> element
-        static ref TEXT_MATCHER_A: Regex = Regex::new(r#"<a[^>]*>(.*?)</a>"#).unwrap();
-    }
+fn scrape_image(
+    figure: ElementRef,
+    label: Option<String>,
+    selectors: &ImageSelectors,
+) -> Option<DevilImage> {
+    // The link points to the full size image, the <img> only to a scaled down thumbnail
+    let src = figure
+        .select(&selectors.link)
+        .next()
+        .and_then(|link| link.value().attr("href"))
+        .or_else(|| {
+            figure
+                .select(&selectors.thumbnail)
+                .next()
+                .and_then(|img| img.value().attr("src"))
+        })?;
+
+    let caption = figure
+        .select(&selectors.caption)
+        .next()
+        .map(|caption| caption.text().collect::<String>().trim().to_string())
+        .filter(|caption| !caption.is_empty());
+
+    Some(DevilImage {
+        src: src.to_string(),
+        label,
+        caption,
+    })
+}
 
-    let response = match reqwest::get(url).await {
-        Ok(html) => html,
-        Err(e) => return Err(Error::new(ErrorKind::Other, e.to_string())),
-    };
-    let html = match response.text().await {
-        Ok(text) => text,
-        Err(e) => return Err(Error::new(ErrorKind::Other, e.to_string())),
-    };
+async fn scrape_devil_detail(
+    fetcher: &dyn Fetcher,
+    selectors: &ScraperSelectors,
+    devil: &Devil,
+) -> Result<DevilDetail, ScrapeError> {
+    let html = fetcher.fetch(&devil.wiki_url).await?;
     let document = Html::parse_document(&html);
 
-    let mut names: HashMap<String, DevilName> = HashMap::new();
+    let infobox = scrape_infobox(&document, selectors);
 
-    let mut gender: Option<String> = None;
-    let mut birthplace: Option<String> = None;
-    let mut status: Option<String> = None;
+    Ok(DevilDetail {
+        id: devil.id.clone(),
+        devil_name: devil.devil_name.clone(),
+        alias_name: devil.alias_name.clone(),
+        wiki_url: devil.wiki_url.clone(),
+        category: devil.category.clone(),
+        names: infobox.names,
+        image_src: infobox.images.first().map(|image| image.src.clone()),
+        images: infobox.images,
+        gender: infobox.gender,
+        birthplace: infobox.birthplace,
+        status: infobox.status,
+        occupations: infobox.occupations,
+        affiliations: infobox.affiliations,
+        contracts: infobox.contracts,
+        relatives: infobox.relatives,
+        abilities: scrape_page_abilities(&document, &selectors.abilities),
+        revision_id: None,
+        revision_timestamp: None,
+        warnings: infobox.warnings,
+    })
+}
 
-    let mut occupations: Vec<String> = Vec::new();
-    let mut affiliations: Vec<String> = Vec::new();
-    let mut contracts: Vec<String> = Vec::new();
-    let mut relatives: Vec<String> = Vec::new();
-
-    let mut abilities: HashMap<String, Vec<Ability>> = HashMap::new();
-
-    // TODO: parse image
-    let abilities_selector: HashMap<&'static str, Selector> = HashMap::from([
-        (
-            "physical",
-            Selector::parse(r#"span[id="Physical_Abilities"]"#).unwrap(),
-        ),
-        (
-            "physical_prowess",
-            Selector::parse(r#"span[id="Physical_Prowess"]"#).unwrap(),
-        ),
-        (
-            "devil",
-            Selector::parse(r#"span[id="Devil_Powers"]"#).unwrap(),
-        ),
-        (
-            "supernatural",
-            Selector::parse(r#"span[id="Supernatural_Abilities"]"#).unwrap(),
-        ),
-    ]);
-    for (ability_type, selector) in abilities_selector {
-        let current_abilities = scrape_abilities(&document, selector);
-        if current_abilities.len() > 0 {
-            abilities.insert(ability_type.to_string(), current_abilities);
+/// Abilities listed under the headings of the Abilities section of a page, in the order of the
+/// page. Known ability headings outside of the Abilities section are kept too
+pub(super) fn scrape_page_abilities(
+    document: &Html,
+    selectors: &AbilitySelectors,
+) -> Vec<AbilitySection> {
+    let mut sections: Vec<AbilitySection> = Vec::new();
+    let mut abilities_level: Option<usize> = None;
+    for headline in document.select(&selectors.headline) {
+        let heading = match headline.parent().and_then(ElementRef::wrap) {
+            Some(heading) => heading,
+            None => continue,
+        };
+        let level = match heading_level(heading.value().name()) {
+            Some(level) => level,
+            None => continue,
+        };
+        let title = headline.text().collect::<String>();
+
+        if !is_in_abilities_section(&mut abilities_level, level, &title) {
+            continue;
         }
+        add_ability_section(&mut sections, &title, scrape_heading_abilities(heading));
     }
 
-    let section_selector =
-        Selector::parse(r#"section[class="pi-item pi-group pi-border-color"]"#).unwrap();
-    for el in document.select(&section_selector) {
-        let h2_selector = Selector::parse("h2").unwrap();
+    sections
+}
+
+fn heading_level(name: &str) -> Option<usize> {
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => name[1..].parse().ok(),
+        _ => None,
+    }
+}
 
-        let h2 = match el.select(&h2_selector).next() {
-            Some(h2) => h2,
+/// Whether the abilities under a heading are kept, `abilities_level` is the level of the
+/// Abilities heading the heading is nested in and is updated as the headings are walked
+pub(super) fn is_in_abilities_section(
+    abilities_level: &mut Option<usize>,
+    level: usize,
+    title: &str,
+) -> bool {
+    if abilities_level.is_some_and(|abilities_level| level <= abilities_level) {
+        *abilities_level = None;
+    }
+
+    let title = title.trim().to_lowercase();
+    if abilities_level.is_none()
+        && matches!(
+            title.as_str(),
+            "abilities" | "powers and abilities" | "abilities and powers"
+        )
+    {
+        *abilities_level = Some(level);
+    }
+
+    abilities_level.is_some() || AbilitySection::known_key(&title).is_some()
+}
+
+/// Adds the abilities listed under a heading, the abilities of a heading whose key is already
+/// in the sections are added to that section
+pub(super) fn add_ability_section(
+    sections: &mut Vec<AbilitySection>,
+    title: &str,
+    abilities: Vec<Ability>,
+) {
+    if abilities.is_empty() {
+        return;
+    }
+
+    let section = AbilitySection::new(title, abilities);
+    match sections.iter_mut().find(|known| known.key == section.key) {
+        Some(known) => known.abilities.extend(section.abilities),
+        None => sections.push(section),
+    }
+}
+
+/// Fields of the portable infobox of a page, which devil and character pages share
+pub(super) struct Infobox {
+    pub names: HashMap<String, DevilName>,
+    pub images: Vec<DevilImage>,
+    pub gender: Option<Gender>,
+    pub birthplace: Option<String>,
+    pub status: Option<Status>,
+    pub occupations: Vec<String>,
+    pub affiliations: Vec<String>,
+    pub contracts: Vec<EntityLink>,
+    pub relatives: Vec<EntityLink>,
+    /// Fields that are missing or couldn't be parsed, they are left empty
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Fields of the Name, Biological and Professional sections of the infobox, a field that can't
+/// be parsed is left empty with a warning instead of failing the whole page
+pub(super) fn scrape_infobox(document: &Html, selectors: &ScraperSelectors) -> Infobox {
+    let mut infobox = Infobox {
+        names: HashMap::new(),
+        images: scrape_images(document, &selectors.images),
+        gender: None,
+        birthplace: None,
+        status: None,
+        occupations: Vec::new(),
+        affiliations: Vec::new(),
+        contracts: Vec::new(),
+        relatives: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    let selectors = &selectors.infobox;
+
+    let mut has_name_section = false;
+    let mut has_biological_section = false;
+    let mut has_professional_section = false;
+    for el in document.select(&selectors.section) {
+        let section_name = match el.select(&selectors.section_title).next() {
+            Some(title) => title.text().collect::<String>(),
             None => continue,
         };
 
-        let section_name = h2.text().collect::<String>();
-        if section_name == SECTION_NAME {
-            {
-                let kanji_selector = Selector::parse(r#"div[data-source="kanji"] > div"#).unwrap();
-                let div = match el.select(&kanji_selector).next() {
-                    Some(div) => div,
-                    None => continue,
-                };
-
-                let kanji = div.text().collect::<Vec<_>>();
-
-                let devil_name = kanji[0].to_string();
-
-                // TODO: handle how to clean the kanjis in the top of the alias name (?)
-                let alias_name: Option<String> = None;
-                // if kanji.len() > 1 {
-                //     alias_name = Some(kanji[1..].join(""));
-                // }
-
-                names.insert(
-                    "kanji".to_string(),
-                    DevilName {
-                        devil_name,
-                        alias_name,
-                    },
-                );
+        if section_name == selectors.name_section {
+            has_name_section = true;
+            scrape_infobox_names(el, selectors, &mut infobox);
+        } else if section_name == selectors.biological_section {
+            has_biological_section = true;
+            let field = &selectors.gender;
+            if let Some(div) = infobox_value(el, field, &mut infobox.warnings) {
+                infobox.gender = non_empty_text(div, &field.source, &mut infobox.warnings)
+                    .map(|gender| Gender::parse(&gender));
             }
-            {
-                let mut romajis: Vec<String> = Vec::new();
-
-                let romaji_selector =
-                    Selector::parse(r#"div[data-source="romaji"] > div > div > i"#).unwrap();
-                for romaji in el.select(&romaji_selector) {
-                    romajis.push(romaji.text().collect::<String>());
-                }
-
-                let devil_name = romajis[0].to_string();
-                let alias_name: Option<String> = if romajis.len() > 1 {
-                    Some(romajis[1].to_string())
-                } else {
-                    None
-                };
-
-                names.insert(
-                    "romaji".to_string(),
-                    DevilName {
-                        devil_name,
-                        alias_name,
-                    },
-                );
+            let field = &selectors.birthplace;
+            if let Some(div) = infobox_value(el, field, &mut infobox.warnings) {
+                infobox.birthplace = non_empty_text(div, &field.source, &mut infobox.warnings);
             }
-        } else if section_name == SECTION_BIOLOGICAL {
-            let gender_selector = Selector::parse(r#"div[data-source="gender"] > div"#).unwrap();
-            let birthplace_selector =
-                Selector::parse(r#"div[data-source="birthplace"] > div"#).unwrap();
-            let status_selector =
-                Selector::parse(r#"div[data-source="status"] > div > div"#).unwrap();
-
-            if let Some(div) = el.select(&gender_selector).next() {
-                gender = Some(div.text().collect::<String>());
+            let field = &selectors.status;
+            if let Some(div) = infobox_value(el, field, &mut infobox.warnings) {
+                infobox.status = non_empty_text(div, &field.source, &mut infobox.warnings)
+                    .map(|status| Status::parse(&status));
             }
-            if let Some(div) = el.select(&birthplace_selector).next() {
-                birthplace = Some(div.text().collect::<String>());
+        } else if section_name == selectors.professional_section {
+            has_professional_section = true;
+            let field = &selectors.occupation;
+            if let Some(div) = infobox_value(el, field, &mut infobox.warnings) {
+                infobox.occupations = div
+                    .text()
+                    .map(|text| text.trim().to_string())
+                    .filter(|text| !text.is_empty())
+                    .collect();
+                warn_if_empty(&infobox.occupations, &field.source, &mut infobox.warnings);
             }
-            if let Some(div) = el.select(&status_selector).next() {
-                status = Some(div.inner_html().trim().to_string());
+            let field = &selectors.affiliation;
+            if let Some(div) = infobox_value(el, field, &mut infobox.warnings) {
+                infobox.affiliations = scrape_affiliations(div, &selectors.nested_item);
+                warn_if_empty(&infobox.affiliations, &field.source, &mut infobox.warnings);
             }
-        } else if section_name == SECTION_PROFESSIONAL {
-            let occupation_selector =
-                Selector::parse(r#"div[data-source="occupation"] > div"#).unwrap();
-            let affiliation_selector =
-                Selector::parse(r#"div[data-source="affiliation"] > div > ul > li"#).unwrap();
-            let contract_selector =
-                Selector::parse(r#"div[data-source="contracted humans"] > div"#).unwrap();
-            let relative_selector =
-                Selector::parse(r#"div[data-source="relatives"] > div"#).unwrap();
-
-            if let Some(div) = el.select(&occupation_selector).next() {
-                let texts = div.text().collect::<Vec<_>>();
-                for t in texts {
-                    occupations.push(t.trim().to_string());
-                }
+            let field = &selectors.contracts;
+            if let Some(div) = infobox_value(el, field, &mut infobox.warnings) {
+                infobox.contracts = scrape_entity_links(div, &selectors.link);
+                warn_if_empty(&infobox.contracts, &field.source, &mut infobox.warnings);
             }
+            let field = &selectors.relatives;
+            if let Some(div) = infobox_value(el, field, &mut infobox.warnings) {
+                infobox.relatives = scrape_entity_links(div, &selectors.link);
+                warn_if_empty(&infobox.relatives, &field.source, &mut infobox.warnings);
+            }
+        }
+    }
 
-            for li in el.select(&affiliation_selector) {
-                let mut curr: Vec<String> = Vec::new();
-
-                let nested_li_selector = Selector::parse(r#"ul > li"#).unwrap();
-                for nested_li in li.select(&nested_li_selector) {
-                    let text = nested_li.text().collect::<String>();
-                    curr.push(text);
-                }
+    if !has_name_section {
+        infobox.warnings.push(ParseWarning::new(
+            "names",
+            &format!("the infobox has no {} section", selectors.name_section),
+        ));
+    }
+    // The fields of a missing section are missing as well
+    if !has_biological_section {
+        for field in [&selectors.gender, &selectors.birthplace, &selectors.status] {
+            warn_missing(field, &mut infobox.warnings);
+        }
+    }
+    if !has_professional_section {
+        for field in [
+            &selectors.occupation,
+            &selectors.affiliation,
+            &selectors.contracts,
+            &selectors.relatives,
+        ] {
+            warn_missing(field, &mut infobox.warnings);
+        }
+    }
 
-                if curr.len() == 0 {
-                    let text = li.text().collect::<String>();
-                    curr.push(text);
-                }
+    infobox
+}
 
-                affiliations.append(&mut curr);
-            }
+fn scrape_infobox_names(section: ElementRef, selectors: &InfoboxSelectors, infobox: &mut Infobox) {
+    let kanji = &selectors.kanji;
+    match section.select(&kanji.value).next() {
+        // The alias is on the line after the name, both may have furigana above their kanji
+        Some(div) => {
+            let mut kanjis = scrape_kanji_lines(div).into_iter();
+            match kanjis.next() {
+                Some((devil_name, reading)) => {
+                    let (alias_name, alias_reading) = match kanjis.next() {
+                        Some((alias_name, alias_reading)) => (Some(alias_name), alias_reading),
+                        None => (None, None),
+                    };
 
-            if let Some(div) = el.select(&contract_selector).next() {
-                let texts = div.text().collect::<Vec<_>>();
-                for t in texts {
-                    contracts.push(t.trim().to_string());
+                    infobox.names.insert(
+                        "kanji".to_string(),
+                        DevilName {
+                            devil_name,
+                            alias_name,
+                            reading,
+                            alias_reading,
+                        },
+                    );
                 }
+                None => infobox
+                    .warnings
+                    .push(ParseWarning::new(&kanji.source, "the kanji name is empty")),
             }
+        }
+        None => infobox.warnings.push(ParseWarning::new(
+            &kanji.source,
+            "the kanji name is missing",
+        )),
+    }
 
-            if let Some(div) = el.select(&relative_selector).next() {
-                let html = div.inner_html();
-                let texts = html.split("<br>").into_iter().collect::<Vec<&str>>();
-                for text in texts {
-                    let result: Option<String> = if TEXT_MATCHER_A.is_match(text) {
-                        if let Some(groups) = TEXT_MATCHER_A.captures(text) {
-                            Some(groups[1].to_string())
-                        } else {
-                            None
-                        }
-                    } else {
-                        Some(text.trim().to_string())
-                    };
+    let romaji = &selectors.romaji;
+    let div = match section.select(&romaji.value).next() {
+        Some(div) => div,
+        None => {
+            infobox.warnings.push(ParseWarning::new(
+                &romaji.source,
+                "the romaji name is missing",
+            ));
+            return;
+        }
+    };
 
-                    if let Some(result) = result {
-                        relatives.push(result);
-                    }
-                }
-            }
+    let mut romajis = div
+        .select(&selectors.romaji_name)
+        .map(|name| name.text().collect::<String>().trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<String>>();
+    if romajis.is_empty() {
+        romajis = div
+            .text()
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .collect();
+        if romajis.is_empty() {
+            infobox.warnings.push(ParseWarning::new(
+                &romaji.source,
+                "the romaji name is empty",
+            ));
+            return;
         }
+
+        infobox.warnings.push(ParseWarning::new(
+            &romaji.source,
+            "no italic romaji name is found, the plain text is used instead",
+        ));
     }
 
-    Ok(DevilDetail {
-        names,
-        image_src: None,
-        gender,
-        birthplace,
-        status,
-        occupations,
-        affiliations,
-        contracts,
-        relatives,
-        abilities,
-    })
+    let mut romajis = romajis.into_iter();
+    if let Some(devil_name) = romajis.next() {
+        infobox.names.insert(
+            "romaji".to_string(),
+            DevilName {
+                devil_name,
+                alias_name: romajis.next(),
+                reading: None,
+                alias_reading: None,
+            },
+        );
+    }
+}
+
+/// Affiliations of the items of the lists of an infobox value, the nested items of an item
+/// are its affiliations
+fn scrape_affiliations(div: ElementRef, nested_item_selector: &Selector) -> Vec<String> {
+    let items = div
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|child| child.value().name() == "ul")
+        .flat_map(|list| list.children().filter_map(ElementRef::wrap))
+        .filter(|child| child.value().name() == "li");
+
+    let mut affiliations: Vec<String> = Vec::new();
+    for li in items {
+        let mut curr = li
+            .select(nested_item_selector)
+            .map(|nested_li| nested_li.text().collect::<String>())
+            .collect::<Vec<String>>();
+
+        if curr.is_empty() {
+            curr.push(li.text().collect::<String>());
+        }
+
+        affiliations.append(&mut curr);
+    }
+
+    affiliations
+}
+
+/// Value of a row of an infobox section, a missing row or a row without a value is a warning
+fn infobox_value<'a>(
+    section: ElementRef<'a>,
+    field: &InfoboxField,
+    warnings: &mut Vec<ParseWarning>,
+) -> Option<ElementRef<'a>> {
+    if section.select(&field.row).next().is_none() {
+        warn_missing(field, warnings);
+        return None;
+    }
+
+    let value = section.select(&field.value).next();
+    if value.is_none() {
+        warnings.push(ParseWarning::new(&field.source, "the field has no value"));
+    }
+    value
+}
+
+fn warn_missing(field: &InfoboxField, warnings: &mut Vec<ParseWarning>) {
+    warnings.push(ParseWarning::new(&field.source, "the field is missing"));
+}
+
+fn non_empty_text(
+    div: ElementRef,
+    field: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> Option<String> {
+    let text = div.text().collect::<String>().trim().to_string();
+    if text.is_empty() {
+        warnings.push(ParseWarning::new(field, "the field is empty"));
+        return None;
+    }
+
+    Some(text)
+}
+
+fn warn_if_empty<T>(values: &[T], field: &str, warnings: &mut Vec<ParseWarning>) {
+    if values.is_empty() {
+        warnings.push(ParseWarning::new(field, "no value could be parsed"));
+    }
+}
+
+/// Links of an infobox value, every line is either links to other pages or the plain name of
+/// an entity without a page
+fn scrape_entity_links(div: ElementRef, link_selector: &Selector) -> Vec<EntityLink> {
+    let mut links: Vec<EntityLink> = Vec::new();
+    for line in div.inner_html().split("<br>") {
+        let fragment = Html::parse_fragment(line);
+        let text = fragment.root_element().text().collect::<String>();
+        let (name, qualifier) = split_qualifier(&text);
+
+        let mut line_links = fragment
+            .select(link_selector)
+            .map(|a| EntityLink {
+                name: split_qualifier(&a.text().collect::<String>()).0,
+                wiki_url: a.value().attr("href").and_then(wiki_url_of_href),
+                entity_id: None,
+                qualifier: qualifier.clone(),
+            })
+            .filter(|link| !link.name.is_empty())
+            .collect::<Vec<EntityLink>>();
+
+        if line_links.is_empty() && !name.is_empty() {
+            line_links.push(EntityLink {
+                name,
+                wiki_url: None,
+                entity_id: None,
+                qualifier,
+            });
+        }
+        links.append(&mut line_links);
+    }
+
+    links
+}
+
+/// Absolute url of the wiki page a link points to, links to pages that don't exist yet or
+/// outside of the wiki have none
+fn wiki_url_of_href(href: &str) -> Option<String> {
+    if href.contains("redlink=1") {
+        return None;
+    }
+
+    let path = href.split(['#', '?']).next().unwrap_or(href);
+    let path = path.strip_prefix(CHAINSAWMAN_WIKI_BASE_URL).unwrap_or(path);
+    if path.starts_with("/wiki/") {
+        Some(format!("{}{}", CHAINSAWMAN_WIKI_BASE_URL, path))
+    } else {
+        None
+    }
+}
+
+/// Splits the text in parentheses off a name, e.g. `Leech Devil (partner)` becomes
+/// `Leech Devil` qualified by `partner`
+pub(super) fn split_qualifier(text: &str) -> (String, Option<String>) {
+    lazy_static! {
+        static ref QUALIFIER_MATCHER: Regex = Regex::new(r#"\(([^()]*)\)"#).unwrap();
+    }
+
+    let qualifier = QUALIFIER_MATCHER
+        .captures(text)
+        .map(|groups| groups[1].trim().to_string())
+        .filter(|qualifier| !qualifier.is_empty());
+    let name = QUALIFIER_MATCHER
+        .replace_all(text, "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (name, qualifier)
 }
@@ -0,0 +1,136 @@
+use std::{error, fmt, io};
+
+#[derive(Debug)]
+pub enum ScrapeError {
+    Network {
+        url: String,
+        message: String,
+    },
+    HttpStatus {
+        url: String,
+        status: u16,
+    },
+    Disallowed {
+        url: String,
+    },
+    NotCached {
+        url: String,
+    },
+    Api {
+        url: String,
+        code: String,
+        info: String,
+    },
+    Io {
+        url: String,
+        error: io::Error,
+    },
+    MissingSection {
+        url: String,
+        section: String,
+    },
+    UnparseableField {
+        url: String,
+        field: String,
+        reason: String,
+    },
+    /// The task scraping the page panicked or was cancelled
+    Task {
+        url: String,
+        message: String,
+    },
+    /// Error that happened while scraping the page of an entity, e.g. a `devil`
+    Page {
+        entity: &'static str,
+        url: String,
+        error: Box<ScrapeError>,
+    },
+}
+
+impl ScrapeError {
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            ScrapeError::Network { .. } => "network",
+            ScrapeError::HttpStatus { .. } => "http_status",
+            ScrapeError::Disallowed { .. } => "disallowed",
+            ScrapeError::NotCached { .. } => "not_cached",
+            ScrapeError::Api { .. } => "api",
+            ScrapeError::Io { .. } => "io",
+            ScrapeError::MissingSection { .. } => "missing_section",
+            ScrapeError::UnparseableField { .. } => "unparseable_field",
+            ScrapeError::Task { .. } => "task",
+            ScrapeError::Page { ref error, .. } => error.kind(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match *self {
+            ScrapeError::Network { ref url, .. } => url,
+            ScrapeError::HttpStatus { ref url, .. } => url,
+            ScrapeError::Disallowed { ref url } => url,
+            ScrapeError::NotCached { ref url } => url,
+            ScrapeError::Api { ref url, .. } => url,
+            ScrapeError::Io { ref url, .. } => url,
+            ScrapeError::MissingSection { ref url, .. } => url,
+            ScrapeError::UnparseableField { ref url, .. } => url,
+            ScrapeError::Task { ref url, .. } => url,
+            ScrapeError::Page { ref url, .. } => url,
+        }
+    }
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ScrapeError::Network {
+                ref url,
+                ref message,
+            } => write!(f, "Unable to fetch {}: {}", url, message),
+            ScrapeError::HttpStatus { ref url, status } => {
+                write!(f, "Fetching {} responded with status {}", url, status)
+            }
+            ScrapeError::Disallowed { ref url } => {
+                write!(f, "Fetching {} is disallowed by robots.txt", url)
+            }
+            ScrapeError::NotCached { ref url } => {
+                write!(f, "{} is not cached, it can't be fetched offline", url)
+            }
+            ScrapeError::Api {
+                ref url,
+                ref code,
+                ref info,
+            } => write!(f, "Fetching {} failed with {}: {}", url, code, info),
+            ScrapeError::Io { ref url, ref error } => {
+                write!(f, "Unable to read {}: {}", url, error)
+            }
+            ScrapeError::MissingSection {
+                ref url,
+                ref section,
+            } => write!(f, "Section {} is missing from {}", section, url),
+            ScrapeError::UnparseableField {
+                ref url,
+                ref field,
+                ref reason,
+            } => write!(f, "Unable to parse {} of {}: {}", field, url, reason),
+            ScrapeError::Task {
+                ref url,
+                ref message,
+            } => write!(f, "The task scraping {} failed: {}", url, message),
+            ScrapeError::Page {
+                entity,
+                ref url,
+                ref error,
+            } => write!(f, "Unable to scrape {} {}: {}", entity, url, error),
+        }
+    }
+}
+
+impl error::Error for ScrapeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            ScrapeError::Io { ref error, .. } => Some(error),
+            ScrapeError::Page { ref error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{
+    header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER},
+    Response, StatusCode, Url,
+};
+use tokio::sync::Mutex;
+
+use super::{
+    CacheMode, CachedResponse, RateLimiter, ResponseCache, RobotsTxt, ScrapeError,
+    RATE_LIMIT_BURST, REQUESTS_PER_SECOND,
+};
+
+#[async_trait]
+pub trait Fetcher: Send + Sync + 'static {
+    /// Fetches the body of the page behind the given url
+    async fn fetch(&self, url: &str) -> Result<String, ScrapeError>;
+}
+
+pub const HTTP_TIMEOUT_MS: u64 = 30000;
+pub const HTTP_MAX_RETRIES: u32 = 3;
+pub const HTTP_INITIAL_BACKOFF_MS: u64 = 1000;
+pub const HTTP_MAX_BACKOFF_MS: u64 = 30000;
+pub const HTTP_MAX_RETRY_AFTER_MS: u64 = 300000;
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "chainsawman-api/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/stevenhansel/chainsawman-api)"
+);
+
+pub struct HttpFetcherConfig {
+    /// Timeout of a single request, including reading its body
+    pub timeout: Duration,
+    /// Number of times a failed request is retried before giving up
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on every following retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff
+    pub max_backoff: Duration,
+    /// Longest wait asked by a `Retry-After` header that is honored, the request fails
+    /// instead of being retried early when the server asks for a longer one
+    pub max_retry_after: Duration,
+    /// Number of requests allowed per second, retries included
+    pub requests_per_second: f64,
+    /// Number of requests allowed at once before being throttled to `requests_per_second`
+    pub burst: u32,
+    /// User agent sent with every request, it is also the agent looked up in robots.txt
+    pub user_agent: String,
+    /// Directory responses are cached in, nothing is cached when not set
+    pub cache_dir: Option<PathBuf>,
+    pub cache_mode: CacheMode,
+}
+
+impl Default for HttpFetcherConfig {
+    fn default() -> Self {
+        HttpFetcherConfig {
+            timeout: Duration::from_millis(HTTP_TIMEOUT_MS),
+            max_retries: HTTP_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(HTTP_INITIAL_BACKOFF_MS),
+            max_backoff: Duration::from_millis(HTTP_MAX_BACKOFF_MS),
+            max_retry_after: Duration::from_millis(HTTP_MAX_RETRY_AFTER_MS),
+            requests_per_second: REQUESTS_PER_SECOND,
+            burst: RATE_LIMIT_BURST,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            cache_dir: None,
+            cache_mode: CacheMode::Revalidate,
+        }
+    }
+}
+
+/// Fetches pages over http, every page is checked against the robots.txt of its site,
+/// which is fetched once and kept for the lifetime of the fetcher
+pub struct HttpFetcher {
+    config: HttpFetcherConfig,
+    client: reqwest::Client,
+    limiter: RateLimiter,
+    robots: Mutex<HashMap<String, Arc<RobotsTxt>>>,
+    cache: Option<ResponseCache>,
+}
+
+impl HttpFetcher {
+    pub fn new(config: HttpFetcherConfig) -> Self {
+        // Building only fails when the TLS backend cannot be initialized, which is
+        // also what makes reqwest::Client::new() panic
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .user_agent(config.user_agent.as_str())
+            .build()
+            .expect("Unable to initialize the http client");
+
+        let limiter = RateLimiter::new(config.requests_per_second, config.burst);
+        let cache = config.cache_dir.clone().map(ResponseCache::new);
+
+        HttpFetcher {
+            config,
+            client,
+            limiter,
+            robots: Mutex::new(HashMap::new()),
+            cache,
+        }
+    }
+
+    fn is_offline(&self) -> bool {
+        self.cache.is_some() && self.config.cache_mode == CacheMode::Offline
+    }
+
+    /// Fetches a page through the cache, a cached page is revalidated with its validators
+    /// unless the cache is refreshed or the fetcher runs offline
+    async fn fetch_cached(&self, url: &str) -> Result<String, ScrapeError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return Ok(self.fetch_with_retries(url, None).await?.body),
+        };
+
+        let cached = match self.config.cache_mode {
+            CacheMode::Refresh => None,
+            CacheMode::Revalidate => cache.get(url).await.unwrap_or_else(|e| {
+                tracing::warn!("Ignoring the unreadable cached response of {}: {}", url, e);
+                None
+            }),
+            CacheMode::Offline => {
+                return match cache.get(url).await {
+                    Ok(Some(cached)) => Ok(cached.body),
+                    Ok(None) => Err(ScrapeError::NotCached {
+                        url: url.to_string(),
+                    }),
+                    Err(error) => Err(ScrapeError::Io {
+                        url: url.to_string(),
+                        error,
+                    }),
+                };
+            }
+        };
+
+        let response = self.fetch_with_retries(url, cached.as_ref()).await?;
+        if let Err(e) = cache.put(&response).await {
+            tracing::warn!("Unable to cache the response of {}: {}", url, e);
+        }
+
+        Ok(response.body)
+    }
+
+    async fn robots_of(&self, url: &Url) -> Arc<RobotsTxt> {
+        let origin = url.origin().ascii_serialization();
+
+        // Held while fetching, so workers wait for the first one to fetch robots.txt
+        let mut robots = self.robots.lock().await;
+        if let Some(robots_txt) = robots.get(&origin) {
+            return robots_txt.clone();
+        }
+
+        let robots_txt = match self.fetch_cached(&format!("{}/robots.txt", origin)).await {
+            Ok(text) => RobotsTxt::parse(&text, &self.config.user_agent),
+            Err(ScrapeError::HttpStatus { status, .. })
+                if (400..500).contains(&status) && status != 429 =>
+            {
+                RobotsTxt::default()
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Unable to read robots.txt, disallowing every page of {}: {}",
+                    origin,
+                    e
+                );
+                RobotsTxt::disallow_all()
+            }
+        };
+
+        if let Some(crawl_delay) = robots_txt.crawl_delay() {
+            tracing::info!(
+                "Honoring a crawl delay of {}ms for {}",
+                crawl_delay.as_millis(),
+                origin
+            );
+            self.limiter.throttle(crawl_delay);
+        }
+
+        let robots_txt = Arc::new(robots_txt);
+        robots.insert(origin, robots_txt.clone());
+
+        robots_txt
+    }
+
+    async fn fetch_with_retries(
+        &self,
+        url: &str,
+        cached: Option<&CachedResponse>,
+    ) -> Result<CachedResponse, ScrapeError> {
+        let mut retry = 0;
+
+        loop {
+            let failure = match self.fetch_once(url, cached).await {
+                Ok(text) => return Ok(text),
+                Err(failure) => failure,
+            };
+
+            if !failure.retryable || retry >= self.config.max_retries {
+                return Err(failure.error);
+            }
+
+            let delay = match failure.retry_after {
+                Some(retry_after) if retry_after > self.config.max_retry_after => {
+                    tracing::warn!(
+                        "Giving up on {}, the server asked to retry in {}s",
+                        url,
+                        retry_after.as_secs()
+                    );
+                    return Err(failure.error);
+                }
+                Some(retry_after) => retry_after,
+                None => self.backoff(retry),
+            };
+            retry += 1;
+
+            tracing::warn!(
+                "Retrying {} in {}ms ({}/{}): {}",
+                url,
+                delay.as_millis(),
+                retry,
+                self.config.max_retries,
+                failure.error
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Fetches a page once, the cached response is sent back when the server answers
+    /// that it has not been modified since
+    async fn fetch_once(
+        &self,
+        url: &str,
+        cached: Option<&CachedResponse>,
+    ) -> Result<CachedResponse, FetchFailure> {
+        self.limiter.acquire().await;
+
+        let mut request = self.client.get(url);
+        if let Some(cached) = cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                return Err(FetchFailure {
+                    error: ScrapeError::Network {
+                        url: url.to_string(),
+                        message: e.to_string(),
+                    },
+                    retryable: true,
+                    retry_after: None,
+                })
+            }
+        };
+
+        let status = response.status();
+        let etag = header_of(&response, ETAG);
+        let last_modified = header_of(&response, LAST_MODIFIED);
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (status, cached) {
+            tracing::debug!("{} has not been modified, using the cached response", url);
+            return Ok(CachedResponse {
+                url: url.to_string(),
+                etag: etag.or_else(|| cached.etag.clone()),
+                last_modified: last_modified.or_else(|| cached.last_modified.clone()),
+                body: cached.body.clone(),
+            });
+        }
+
+        if !status.is_success() {
+            return Err(FetchFailure {
+                error: ScrapeError::HttpStatus {
+                    url: url.to_string(),
+                    status: status.as_u16(),
+                },
+                retryable: is_retryable_status(status),
+                retry_after: retry_after(&response),
+            });
+        }
+
+        let body = response.text().await.map_err(|e| FetchFailure {
+            error: ScrapeError::Network {
+                url: url.to_string(),
+                message: e.to_string(),
+            },
+            retryable: true,
+            retry_after: None,
+        })?;
+
+        Ok(CachedResponse {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            body,
+        })
+    }
+
+    /// Exponential backoff of the given retry with jitter, so workers that failed
+    /// together don't hit the wiki again at the same time
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.config.max_backoff);
+
+        let half = backoff / 2;
+        half + half.mul_f64(rand::random::<f64>())
+    }
+}
+
+impl Default for HttpFetcher {
+    fn default() -> Self {
+        Self::new(HttpFetcherConfig::default())
+    }
+}
+
+#[async_trait]
+impl Fetcher for HttpFetcher {
+    async fn fetch(&self, url: &str) -> Result<String, ScrapeError> {
+        // An invalid url is left for reqwest to report, and nothing is requested offline
+        if let (Ok(parsed), false) = (Url::parse(url), self.is_offline()) {
+            let path = match parsed.query() {
+                Some(query) => format!("{}?{}", parsed.path(), query),
+                None => parsed.path().to_string(),
+            };
+
+            if !self.robots_of(&parsed).await.is_allowed(&path) {
+                return Err(ScrapeError::Disallowed {
+                    url: url.to_string(),
+                });
+            }
+        }
+
+        self.fetch_cached(url).await
+    }
+}
+
+struct FetchFailure {
+    error: ScrapeError,
+    retryable: bool,
+    retry_after: Option<Duration>,
+}
+
+fn header_of(response: &Response, name: HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Reads the `Retry-After` header of a 429 or 503 response, which is either
+/// a number of seconds or an http date
+fn retry_after(response: &Response) -> Option<Duration> {
+    if !matches!(
+        response.status(),
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        return None;
+    }
+
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&Utc) - Utc::now();
+    Some(delay.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Serves saved wiki pages from a directory, where the page behind
+/// `https://chainsaw-man.fandom.com/wiki/Bat_Devil` is read from `<root>/wiki/Bat_Devil.html`
+pub struct FileFetcher {
+    root: PathBuf,
+}
+
+impl FileFetcher {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        FileFetcher { root: root.into() }
+    }
+
+    fn path_of(&self, url: &str) -> Option<PathBuf> {
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/').map(|(_, path)| path)?,
+            None => url.trim_start_matches('/'),
+        };
+        let path = path.split(['?', '#']).next()?;
+
+        if path.is_empty() || path.split('/').any(|segment| segment == "..") {
+            return None;
+        }
+
+        Some(self.root.join(format!("{}.html", path)))
+    }
+}
+
+#[async_trait]
+impl Fetcher for FileFetcher {
+    async fn fetch(&self, url: &str) -> Result<String, ScrapeError> {
+        let path = match self.path_of(url) {
+            Some(path) => path,
+            None => {
+                return Err(ScrapeError::Io {
+                    url: url.to_string(),
+                    error: Error::new(
+                        ErrorKind::InvalidInput,
+                        "url does not point to a saved page",
+                    ),
+                })
+            }
+        };
+
+        tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| ScrapeError::Io {
+                url: url.to_string(),
+                error: Error::new(e.kind(), format!("{}: {}", path.display(), e)),
+            })
+    }
+}
@@ -0,0 +1,79 @@
+use std::{sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+
+pub const REQUESTS_PER_SECOND: f64 = 1.0;
+pub const RATE_LIMIT_BURST: u32 = 3;
+
+/// Token bucket shared by every request made to the wiki, it holds up to `burst` tokens
+/// and refills `requests_per_second` tokens every second
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    requests_per_second: f64,
+    burst: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    /// `requests_per_second` must be greater than 0, a burst of 0 is treated as 1
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        let burst = burst.max(1) as f64;
+
+        RateLimiter {
+            bucket: Mutex::new(Bucket {
+                requests_per_second,
+                burst,
+                tokens: burst,
+                updated_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Waits until a request is allowed to be made
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+            bucket.tokens =
+                (bucket.tokens + elapsed * bucket.requests_per_second).min(bucket.burst);
+            bucket.updated_at = now;
+
+            // The token is reserved right away and may go into debt, so waiting requests
+            // are let through in the order they asked instead of racing for the next token
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+
+            Duration::from_secs_f64(-bucket.tokens / bucket.requests_per_second)
+        };
+
+        tokio::time::sleep(wait).await;
+    }
+
+    /// Slows the limiter down to at most one request per interval starting from now,
+    /// e.g. to honor the `Crawl-delay` of robots.txt, a limiter that is already slower
+    /// keeps its rate
+    pub fn throttle(&self, interval: Duration) {
+        if interval.is_zero() {
+            return;
+        }
+
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.requests_per_second = bucket.requests_per_second.min(1.0 / interval.as_secs_f64());
+        bucket.burst = 1.0;
+        bucket.tokens = bucket.tokens.min(0.0);
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(REQUESTS_PER_SECOND, RATE_LIMIT_BURST)
+    }
+}
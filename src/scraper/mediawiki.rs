@@ -0,0 +1,886 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    models::{
+        Ability, AbilitySection, Character, CharacterDetail, Devil, DevilCategory, DevilDetail,
+        DevilImage, DevilName, EntityLink, Gender, ParseWarning, Revision, ScrapeResult, Status,
+    },
+    services::{CharacterDataSource, DevilDataSource},
+};
+
+use super::{
+    devil::{
+        add_ability_section, devil_id_from_url, is_in_abilities_section, scrape_details,
+        split_qualifier, GalleryLink, Infobox, WikiPage, CHAINSAWMAN_WIKI_BASE_URL,
+    },
+    selectors::Gallery,
+    wikitext::{self, Template},
+    DevilScraperConfig, Fetcher, ScrapeError, ScraperSelectors,
+};
+
+pub const CHAINSAWMAN_WIKI_API_URL: &str = "https://chainsaw-man.fandom.com/api.php";
+
+const DEVILS_PAGE: &str = "Devil";
+const CHARACTERS_PAGE: &str = "Characters";
+
+/// Number of titles the API accepts in a single query
+const MAX_TITLES_PER_QUERY: usize = 50;
+
+/// Characters left as is in a query value
+const QUERY_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Characters left as is in the path of a wiki page, the way the wiki links its pages
+const PAGE_PATH: &AsciiSet = &QUERY_VALUE
+    .remove(b'/')
+    .remove(b':')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b',')
+    .remove(b'!');
+
+/// Scrapes the devils from the wikitext served by the MediaWiki Action API of the wiki,
+/// which doesn't depend on how the wiki skin renders its pages
+pub struct MediaWikiScraper {
+    config: DevilScraperConfig,
+    fetcher: Arc<dyn Fetcher>,
+}
+
+impl MediaWikiScraper {
+    pub fn new(config: DevilScraperConfig, fetcher: Arc<dyn Fetcher>) -> Self {
+        MediaWikiScraper { config, fetcher }
+    }
+}
+
+#[async_trait]
+impl DevilDataSource for MediaWikiScraper {
+    async fn scrape(&self, devils: &[Devil]) -> Result<ScrapeResult, ScrapeError> {
+        let fetcher = self.fetcher.clone();
+
+        Ok(
+            scrape_details(self.config.num_of_workers, devils, move |devil| {
+                let fetcher = fetcher.clone();
+                async move { scrape_devil_detail(fetcher.as_ref(), &devil).await }
+            })
+            .await,
+        )
+    }
+
+    async fn scrape_devils(&self) -> Result<Vec<Devil>, ScrapeError> {
+        scrape_devils(self.fetcher.as_ref(), &self.config.selectors).await
+    }
+
+    async fn scrape_devil_detail(&self, devil: &Devil) -> Result<DevilDetail, ScrapeError> {
+        scrape_devil_detail(self.fetcher.as_ref(), devil).await
+    }
+
+    async fn scrape_revisions(
+        &self,
+        devils: &[Devil],
+    ) -> Result<HashMap<String, Revision>, ScrapeError> {
+        fetch_revisions(self.fetcher.as_ref(), devils).await
+    }
+}
+
+#[async_trait]
+impl CharacterDataSource for MediaWikiScraper {
+    async fn scrape_character_details(
+        &self,
+        characters: &[Character],
+    ) -> Result<ScrapeResult<CharacterDetail>, ScrapeError> {
+        let fetcher = self.fetcher.clone();
+
+        Ok(
+            scrape_details(self.config.num_of_workers, characters, move |character| {
+                let fetcher = fetcher.clone();
+                async move { scrape_character_detail(fetcher.as_ref(), &character).await }
+            })
+            .await,
+        )
+    }
+
+    async fn scrape_characters(&self) -> Result<Vec<Character>, ScrapeError> {
+        scrape_characters(self.fetcher.as_ref(), &self.config.selectors).await
+    }
+
+    async fn scrape_character_detail(
+        &self,
+        character: &Character,
+    ) -> Result<CharacterDetail, ScrapeError> {
+        scrape_character_detail(self.fetcher.as_ref(), character).await
+    }
+
+    async fn scrape_character_revisions(
+        &self,
+        characters: &[Character],
+    ) -> Result<HashMap<String, Revision>, ScrapeError> {
+        fetch_revisions(self.fetcher.as_ref(), characters).await
+    }
+}
+
+#[derive(Deserialize)]
+struct ParseResponse {
+    parse: Option<ParsedPage>,
+    error: Option<ErrorInfo>,
+}
+
+#[derive(Deserialize)]
+struct ParsedPage {
+    wikitext: String,
+}
+
+#[derive(Deserialize)]
+struct QueryResponse {
+    query: Option<Query>,
+    error: Option<ErrorInfo>,
+}
+
+#[derive(Deserialize)]
+struct Query {
+    #[serde(default)]
+    normalized: Vec<Normalized>,
+    #[serde(default)]
+    redirects: Vec<Normalized>,
+    #[serde(default)]
+    pages: Vec<QueryPage>,
+}
+
+#[derive(Deserialize)]
+struct Normalized {
+    from: String,
+    to: String,
+}
+
+#[derive(Deserialize)]
+struct QueryPage {
+    title: String,
+    #[serde(default)]
+    imageinfo: Vec<ImageInfo>,
+    #[serde(default)]
+    revisions: Vec<RevisionInfo>,
+}
+
+#[derive(Deserialize)]
+struct ImageInfo {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct RevisionInfo {
+    revid: u64,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct ErrorInfo {
+    code: String,
+    info: String,
+}
+
+/// Image of the infobox before its url is resolved
+struct InfoboxImage {
+    file: String,
+    label: Option<String>,
+    caption: Option<String>,
+}
+
+async fn fetch_json<T: DeserializeOwned>(
+    fetcher: &dyn Fetcher,
+    url: &str,
+) -> Result<T, ScrapeError> {
+    let body = fetcher.fetch(url).await?;
+
+    serde_json::from_str(&body).map_err(|e| ScrapeError::UnparseableField {
+        url: url.to_string(),
+        field: "response".to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Fetches the wikitext of a page, returning it with the url it was fetched from
+async fn fetch_wikitext(
+    fetcher: &dyn Fetcher,
+    title: &str,
+) -> Result<(String, String), ScrapeError> {
+    let url = format!(
+        "{}?action=parse&page={}&prop=wikitext&redirects=1&format=json&formatversion=2",
+        CHAINSAWMAN_WIKI_API_URL,
+        utf8_percent_encode(title, QUERY_VALUE)
+    );
+
+    let response: ParseResponse = fetch_json(fetcher, &url).await?;
+    match (response.parse, response.error) {
+        (Some(page), _) => Ok((url, page.wikitext)),
+        (None, Some(error)) => Err(ScrapeError::Api {
+            url,
+            code: error.code,
+            info: error.info,
+        }),
+        (None, None) => Err(ScrapeError::UnparseableField {
+            url,
+            field: "response".to_string(),
+            reason: "the response has neither a page nor an error".to_string(),
+        }),
+    }
+}
+
+/// Resolves the urls of the given files, a file that doesn't exist is left out
+async fn fetch_image_urls(
+    fetcher: &dyn Fetcher,
+    files: &[&str],
+) -> Result<HashMap<String, String>, ScrapeError> {
+    let titles = files
+        .iter()
+        .map(|file| format!("File:{}", file))
+        .collect::<Vec<String>>();
+    let url = format!(
+        "{}?action=query&prop=imageinfo&iiprop=url&titles={}&format=json&formatversion=2",
+        CHAINSAWMAN_WIKI_API_URL,
+        utf8_percent_encode(&titles.join("|"), QUERY_VALUE)
+    );
+
+    let response: QueryResponse = fetch_json(fetcher, &url).await?;
+    let query = match (response.query, response.error) {
+        (Some(query), _) => query,
+        (None, Some(error)) => {
+            return Err(ScrapeError::Api {
+                url,
+                code: error.code,
+                info: error.info,
+            })
+        }
+        (None, None) => return Ok(HashMap::new()),
+    };
+
+    let normalized = query
+        .normalized
+        .into_iter()
+        .map(|normalized| (normalized.from, normalized.to))
+        .collect::<HashMap<String, String>>();
+    let mut urls = query
+        .pages
+        .into_iter()
+        .filter_map(|page| {
+            let url = page.imageinfo.into_iter().next()?.url;
+            Some((page.title, url))
+        })
+        .collect::<HashMap<String, String>>();
+
+    Ok(files
+        .iter()
+        .zip(titles.iter())
+        .filter_map(|(file, title)| {
+            let title = normalized.get(title).unwrap_or(title);
+            urls.remove(title).map(|url| (file.to_string(), url))
+        })
+        .collect())
+}
+
+/// Fetches the latest revision of every page, keyed by the id of the page. A page that
+/// doesn't exist is left out
+pub(super) async fn fetch_revisions<P: WikiPage>(
+    fetcher: &dyn Fetcher,
+    pages: &[P],
+) -> Result<HashMap<String, Revision>, ScrapeError> {
+    // key: Title of the page, value: ids of the pages with the title
+    let mut ids_by_title: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for page in pages {
+        ids_by_title
+            .entry(title_of(page.wiki_url()))
+            .or_default()
+            .push(page.id());
+    }
+
+    let titles = ids_by_title.keys().collect::<Vec<&String>>();
+    let mut revisions: HashMap<String, Revision> = HashMap::new();
+
+    for titles in titles.chunks(MAX_TITLES_PER_QUERY) {
+        let joined = titles
+            .iter()
+            .map(|title| title.as_str())
+            .collect::<Vec<&str>>()
+            .join("|");
+        let url = format!(
+            "{}?action=query&prop=revisions&rvprop=ids%7Ctimestamp&titles={}&redirects=1&format=json&formatversion=2",
+            CHAINSAWMAN_WIKI_API_URL,
+            utf8_percent_encode(&joined, QUERY_VALUE)
+        );
+
+        let response: QueryResponse = fetch_json(fetcher, &url).await?;
+        let query = match (response.query, response.error) {
+            (Some(query), _) => query,
+            (None, Some(error)) => {
+                return Err(ScrapeError::Api {
+                    url,
+                    code: error.code,
+                    info: error.info,
+                })
+            }
+            (None, None) => continue,
+        };
+
+        let normalized = query
+            .normalized
+            .into_iter()
+            .map(|normalized| (normalized.from, normalized.to))
+            .collect::<HashMap<String, String>>();
+        let redirects = query
+            .redirects
+            .into_iter()
+            .map(|redirect| (redirect.from, redirect.to))
+            .collect::<HashMap<String, String>>();
+        let latest = query
+            .pages
+            .into_iter()
+            .filter_map(|page| {
+                let revision = page.revisions.into_iter().next()?;
+                Some((
+                    page.title,
+                    Revision {
+                        id: revision.revid,
+                        timestamp: revision.timestamp,
+                    },
+                ))
+            })
+            .collect::<HashMap<String, Revision>>();
+
+        for title in titles {
+            let resolved = normalized.get(*title).unwrap_or(title);
+            let resolved = redirects.get(resolved).unwrap_or(resolved);
+
+            if let Some(revision) = latest.get(resolved) {
+                for id in &ids_by_title[*title] {
+                    revisions.insert(id.to_string(), *revision);
+                }
+            }
+        }
+    }
+
+    Ok(revisions)
+}
+
+fn wiki_url_of(title: &str) -> String {
+    format!(
+        "{}/wiki/{}",
+        CHAINSAWMAN_WIKI_BASE_URL,
+        utf8_percent_encode(&title.trim().replace(' ', "_"), PAGE_PATH)
+    )
+}
+
+fn title_of(wiki_url: &str) -> String {
+    let path = wiki_url.rsplit("/wiki/").next().unwrap_or(wiki_url);
+    percent_decode_str(path)
+        .decode_utf8_lossy()
+        .replace('_', " ")
+}
+
+/// Scrapes the links of the category galleries of a page, `galleries` maps every category
+/// to the position of its `<gallery>` in the page
+async fn scrape_gallery_links(
+    fetcher: &dyn Fetcher,
+    page: &str,
+    galleries: &[Gallery],
+) -> Result<Vec<GalleryLink>, ScrapeError> {
+    let (url, text) = fetch_wikitext(fetcher, page).await?;
+    let page_galleries = wikitext::galleries(&text);
+
+    let mut links: Vec<GalleryLink> = Vec::new();
+    for gallery in galleries {
+        let lines = match gallery
+            .position
+            .and_then(|position| page_galleries.get(position))
+        {
+            Some(lines) => lines,
+            None => {
+                return Err(ScrapeError::MissingSection {
+                    url,
+                    section: gallery.id.clone(),
+                })
+            }
+        };
+
+        for line in lines {
+            // e.g. `Nayuta.png|[[Nayuta|Nayuta/Control Devil]]`
+            let link = match wikitext::links(line)
+                .into_iter()
+                .find(|link| !is_file(&link.target))
+            {
+                Some(link) => link,
+                None => continue,
+            };
+
+            links.push(GalleryLink::new(
+                wiki_url_of(&link.target),
+                &link.label,
+                &gallery.category,
+            ));
+        }
+    }
+
+    Ok(links)
+}
+
+async fn scrape_devils(
+    fetcher: &dyn Fetcher,
+    selectors: &ScraperSelectors,
+) -> Result<Vec<Devil>, ScrapeError> {
+    let links = scrape_gallery_links(fetcher, DEVILS_PAGE, &selectors.galleries.devils).await?;
+
+    Ok(links
+        .into_iter()
+        .map(|link| Devil {
+            id: devil_id_from_url(&link.wiki_url),
+            devil_name: link.name,
+            alias_name: link.alias_name,
+            wiki_url: link.wiki_url,
+            category: DevilCategory::parse(&link.category),
+        })
+        .collect())
+}
+
+async fn scrape_characters(
+    fetcher: &dyn Fetcher,
+    selectors: &ScraperSelectors,
+) -> Result<Vec<Character>, ScrapeError> {
+    let links =
+        scrape_gallery_links(fetcher, CHARACTERS_PAGE, &selectors.galleries.characters).await?;
+
+    Ok(links
+        .into_iter()
+        .map(|link| Character {
+            id: devil_id_from_url(&link.wiki_url),
+            name: link.name,
+            alias_name: link.alias_name,
+            wiki_url: link.wiki_url,
+            category: link.category,
+        })
+        .collect())
+}
+
+async fn scrape_devil_detail(
+    fetcher: &dyn Fetcher,
+    devil: &Devil,
+) -> Result<DevilDetail, ScrapeError> {
+    let (url, text) = fetch_wikitext(fetcher, &title_of(&devil.wiki_url)).await?;
+    let infobox = scrape_infobox(fetcher, &url, &text).await?;
+
+    Ok(DevilDetail {
+        id: devil.id.clone(),
+        devil_name: devil.devil_name.clone(),
+        alias_name: devil.alias_name.clone(),
+        wiki_url: devil.wiki_url.clone(),
+        category: devil.category.clone(),
+        names: infobox.names,
+        image_src: infobox.images.first().map(|image| image.src.clone()),
+        images: infobox.images,
+        gender: infobox.gender,
+        birthplace: infobox.birthplace,
+        status: infobox.status,
+        occupations: infobox.occupations,
+        affiliations: infobox.affiliations,
+        contracts: infobox.contracts,
+        relatives: infobox.relatives,
+        abilities: scrape_abilities(&text),
+        revision_id: None,
+        revision_timestamp: None,
+        warnings: infobox.warnings,
+    })
+}
+
+async fn scrape_character_detail(
+    fetcher: &dyn Fetcher,
+    character: &Character,
+) -> Result<CharacterDetail, ScrapeError> {
+    let (url, text) = fetch_wikitext(fetcher, &title_of(&character.wiki_url)).await?;
+    let infobox = scrape_infobox(fetcher, &url, &text).await?;
+
+    Ok(CharacterDetail {
+        id: character.id.clone(),
+        name: character.name.clone(),
+        alias_name: character.alias_name.clone(),
+        wiki_url: character.wiki_url.clone(),
+        category: character.category.clone(),
+        names: infobox.names,
+        image_src: infobox.images.first().map(|image| image.src.clone()),
+        images: infobox.images,
+        gender: infobox.gender,
+        birthplace: infobox.birthplace,
+        status: infobox.status,
+        occupations: infobox.occupations,
+        affiliations: infobox.affiliations,
+        contracts: infobox.contracts,
+        relatives: infobox.relatives,
+        abilities: scrape_abilities(&text),
+        revision_id: None,
+        revision_timestamp: None,
+        warnings: infobox.warnings,
+    })
+}
+
+async fn scrape_infobox(
+    fetcher: &dyn Fetcher,
+    url: &str,
+    text: &str,
+) -> Result<Infobox, ScrapeError> {
+    let infobox = wikitext::find_template(text, |name| name.to_lowercase().starts_with("infobox"))
+        .ok_or_else(|| ScrapeError::MissingSection {
+            url: url.to_string(),
+            section: "infobox".to_string(),
+        })?;
+
+    let mut warnings: Vec<ParseWarning> = Vec::new();
+
+    let mut names: HashMap<String, DevilName> = HashMap::new();
+    match infobox.param("kanji") {
+        Some(kanji) => {
+            // The alias is on the line after the name, both may have furigana above their kanji
+            let mut kanjis = wikitext::raw_lines(kanji)
+                .into_iter()
+                .map(|line| wikitext::ruby_text(&line))
+                .filter(|(kanji, _)| !kanji.is_empty());
+            match kanjis.next() {
+                Some((devil_name, reading)) => {
+                    let (alias_name, alias_reading) = match kanjis.next() {
+                        Some((alias_name, alias_reading)) => (Some(alias_name), alias_reading),
+                        None => (None, None),
+                    };
+
+                    names.insert(
+                        "kanji".to_string(),
+                        DevilName {
+                            devil_name,
+                            alias_name,
+                            reading,
+                            alias_reading,
+                        },
+                    );
+                }
+                None => warnings.push(ParseWarning::new("kanji", "the kanji name is empty")),
+            }
+        }
+        None => warnings.push(ParseWarning::new("kanji", "the kanji name is missing")),
+    }
+    match infobox.param("romaji") {
+        Some(romaji) => {
+            let mut romajis = wikitext::italics(romaji);
+            if romajis.is_empty() {
+                romajis = wikitext::lines(romaji);
+                if !romajis.is_empty() {
+                    warnings.push(ParseWarning::new(
+                        "romaji",
+                        "no italic romaji name is found, the plain text is used instead",
+                    ));
+                }
+            }
+
+            let mut romajis = romajis.into_iter();
+            match romajis.next() {
+                Some(devil_name) => {
+                    names.insert(
+                        "romaji".to_string(),
+                        DevilName {
+                            devil_name,
+                            alias_name: romajis.next(),
+                            reading: None,
+                            alias_reading: None,
+                        },
+                    );
+                }
+                None => warnings.push(ParseWarning::new("romaji", "the romaji name is empty")),
+            }
+        }
+        None => warnings.push(ParseWarning::new("romaji", "the romaji name is missing")),
+    }
+
+    // A missing parameter and a parameter given without a value are both warnings
+    let mut warn_if_empty = |key: &str, is_empty: bool| {
+        if infobox.param(key).is_none() {
+            warnings.push(ParseWarning::new(key, "the field is missing"));
+        } else if is_empty {
+            warnings.push(ParseWarning::new(key, "no value could be parsed"));
+        }
+    };
+
+    let text_of = |key: &str| {
+        infobox
+            .param(key)
+            .map(wikitext::plain_text)
+            .filter(|text| !text.is_empty())
+    };
+    let lines_of = |key: &str| infobox.param(key).map(wikitext::lines).unwrap_or_default();
+
+    let links_of = |key: &str| infobox.param(key).map(entity_links).unwrap_or_default();
+
+    let gender = text_of("gender").map(|gender| Gender::parse(&gender));
+    warn_if_empty("gender", gender.is_none());
+    let birthplace = text_of("birthplace");
+    warn_if_empty("birthplace", birthplace.is_none());
+    let status = text_of("status").map(|status| Status::parse(&status));
+    warn_if_empty("status", status.is_none());
+    let occupations = lines_of("occupation");
+    warn_if_empty("occupation", occupations.is_empty());
+    let affiliations = lines_of("affiliation");
+    warn_if_empty("affiliation", affiliations.is_empty());
+    let contracts = links_of("contracted humans");
+    warn_if_empty("contracted humans", contracts.is_empty());
+    let relatives = links_of("relatives");
+    warn_if_empty("relatives", relatives.is_empty());
+
+    let infobox_images = infobox_images(&infobox);
+    let mut images: Vec<DevilImage> = Vec::new();
+    if !infobox_images.is_empty() {
+        let files = infobox_images
+            .iter()
+            .map(|image| image.file.as_str())
+            .collect::<Vec<&str>>();
+        // The rest of the page is still scraped when the image lookup fails
+        match fetch_image_urls(fetcher, &files).await {
+            Ok(urls) => {
+                for image in infobox_images {
+                    match urls.get(&image.file) {
+                        Some(src) => images.push(DevilImage {
+                            src: src.clone(),
+                            label: image.label,
+                            caption: image.caption,
+                        }),
+                        None => tracing::warn!("Image {} of {} does not exist", image.file, url),
+                    }
+                }
+            }
+            Err(err) => warnings.push(ParseWarning::new(
+                "image",
+                &format!("the image urls could not be fetched: {}", err),
+            )),
+        }
+    }
+
+    Ok(Infobox {
+        names,
+        images,
+        gender,
+        birthplace,
+        status,
+        occupations,
+        affiliations,
+        contracts,
+        relatives,
+        warnings,
+    })
+}
+
+/// Links of an infobox parameter, every line is either links to other pages or the plain name
+/// of an entity without a page
+fn entity_links(text: &str) -> Vec<EntityLink> {
+    let mut links: Vec<EntityLink> = Vec::new();
+    for line in wikitext::raw_lines(text) {
+        let (name, qualifier) = split_qualifier(&wikitext::plain_text(&line));
+
+        let mut line_links = wikitext::links(&line)
+            .into_iter()
+            .filter(|link| !is_file(&link.target))
+            .map(|link| EntityLink {
+                name: split_qualifier(&link.label).0,
+                wiki_url: page_of(&link.target).map(|page| wiki_url_of(&page)),
+                entity_id: None,
+                qualifier: qualifier.clone(),
+            })
+            .filter(|link| !link.name.is_empty())
+            .collect::<Vec<EntityLink>>();
+
+        if line_links.is_empty() && !name.is_empty() {
+            line_links.push(EntityLink {
+                name,
+                wiki_url: None,
+                entity_id: None,
+                qualifier,
+            });
+        }
+        links.append(&mut line_links);
+    }
+
+    links
+}
+
+/// Title of the page a link targets without its section, the first letter of a title being
+/// case insensitive like on the wiki
+fn page_of(target: &str) -> Option<String> {
+    let page = target.trim_start_matches(':').split('#').next()?.trim();
+
+    let mut chars = page.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+fn is_file(target: &str) -> bool {
+    let target = target.to_lowercase();
+    target.starts_with("file:") || target.starts_with("image:")
+}
+
+/// File name of an image, either a `[[File:...]]` link or a bare file name
+fn file_of(text: &str) -> Option<String> {
+    let file = match wikitext::links(text)
+        .into_iter()
+        .find(|link| is_file(&link.target))
+    {
+        Some(link) => link.target.split_once(':')?.1.to_string(),
+        None => wikitext::plain_text(text),
+    };
+
+    let file = file.trim();
+    if file.is_empty() {
+        None
+    } else {
+        Some(file.to_string())
+    }
+}
+
+/// Images of the infobox, tabbed alternate images are either a `<gallery>` whose captions
+/// are the tab titles or a `<tabber>`
+fn infobox_images(infobox: &Template) -> Vec<InfoboxImage> {
+    let image = match infobox.param("image") {
+        Some(image) => image,
+        None => return Vec::new(),
+    };
+
+    let galleries = wikitext::galleries(image);
+    if !galleries.is_empty() {
+        return galleries
+            .iter()
+            .flatten()
+            .filter_map(|line| {
+                let (file, label) = match line.split_once('|') {
+                    Some((file, label)) => (file, Some(wikitext::plain_text(label))),
+                    None => (line.as_str(), None),
+                };
+
+                Some(InfoboxImage {
+                    file: file_of(file)?,
+                    label: label.filter(|label| !label.is_empty()),
+                    caption: None,
+                })
+            })
+            .collect();
+    }
+
+    let tabs = wikitext::tabs(image);
+    if !tabs.is_empty() {
+        return tabs
+            .iter()
+            .filter_map(|(title, content)| {
+                Some(InfoboxImage {
+                    file: file_of(content)?,
+                    label: Some(title.clone()).filter(|title| !title.is_empty()),
+                    caption: None,
+                })
+            })
+            .collect();
+    }
+
+    match file_of(image) {
+        Some(file) => vec![InfoboxImage {
+            file,
+            label: None,
+            caption: infobox
+                .param("caption")
+                .map(wikitext::plain_text)
+                .filter(|caption| !caption.is_empty()),
+        }],
+        None => Vec::new(),
+    }
+}
+
+/// Abilities listed under the headings of the Abilities section, in the order of the page
+fn scrape_abilities(text: &str) -> Vec<AbilitySection> {
+    let mut sections: Vec<AbilitySection> = Vec::new();
+    let mut abilities_level: Option<usize> = None;
+    for section in wikitext::sections(text) {
+        if !is_in_abilities_section(&mut abilities_level, section.level, &section.title) {
+            continue;
+        }
+        add_ability_section(
+            &mut sections,
+            &section.title,
+            parse_abilities(&section.body),
+        );
+    }
+
+    sections
+}
+
+/// Parses the bullet list an ability section starts with, nested bullets being the
+/// abilities of their parent
+fn parse_abilities(body: &str) -> Vec<Ability> {
+    let mut items: Vec<(usize, String)> = Vec::new();
+    let mut in_gallery = false;
+
+    for line in body.lines() {
+        let line = line.trim();
+        if in_gallery {
+            in_gallery = !line.to_lowercase().starts_with("</gallery>");
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let depth = line.chars().take_while(|c| *c == '*').count();
+        if depth > 0 {
+            items.push((depth, line[depth..].trim().to_string()));
+            continue;
+        }
+
+        // Images between the abilities are skipped, any other paragraph ends the list
+        let lowercase = line.to_lowercase();
+        if lowercase.starts_with("<gallery") {
+            in_gallery = !lowercase.contains("</gallery>");
+        } else if !(lowercase.starts_with("[[file:") || lowercase.starts_with("[[image:")) {
+            break;
+        }
+    }
+
+    build_abilities(&items, &mut 0, 1)
+}
+
+fn build_abilities(items: &[(usize, String)], index: &mut usize, depth: usize) -> Vec<Ability> {
+    let mut abilities: Vec<Ability> = Vec::new();
+
+    while let Some((item_depth, text)) = items.get(*index) {
+        if *item_depth < depth {
+            break;
+        }
+        *index += 1;
+
+        let child_abilities = build_abilities(items, index, item_depth + 1);
+
+        let text = wikitext::plain_text(text);
+        let (name, description) = match text.split_once(':') {
+            Some((name, description)) => (name.trim(), description.trim()),
+            None => (text.trim(), ""),
+        };
+
+        // A bullet without text only groups the abilities of its nested bullets
+        if name.is_empty() {
+            abilities.extend(child_abilities);
+            continue;
+        }
+
+        abilities.push(Ability {
+            name: name.to_string(),
+            description: description.to_string(),
+            abilities: child_abilities,
+        });
+    }
+
+    abilities
+}
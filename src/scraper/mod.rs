@@ -1,3 +1,20 @@
+pub mod cache;
+pub mod character;
 pub mod devil;
+pub mod errors;
+pub mod fetcher;
+pub mod limiter;
+pub mod mediawiki;
+pub mod robots;
+pub mod selectors;
+pub mod wikitext;
 
+pub use cache::*;
+pub use character::*;
 pub use devil::*;
+pub use errors::*;
+pub use fetcher::*;
+pub use limiter::*;
+pub use mediawiki::*;
+pub use robots::*;
+pub use selectors::*;
@@ -0,0 +1,328 @@
+use std::{fmt, fs, io, path::Path, sync::Arc};
+
+use lazy_static::lazy_static;
+use scraper::Selector;
+use serde::Deserialize;
+
+/// Version of the selector mapping format, bumped whenever its keys change in a way older
+/// mappings can't be read
+pub const SELECTORS_SCHEMA_VERSION: u32 = 1;
+
+/// Mapping of the current layout of the wiki, used when no other mapping is given
+pub const DEFAULT_SELECTORS: &str = include_str!("selectors.toml");
+
+lazy_static! {
+    static ref BUILTIN_SELECTORS: Arc<ScraperSelectors> =
+        Arc::new(ScraperSelectors::from_toml(DEFAULT_SELECTORS).unwrap());
+}
+
+/**
+* Compiled selectors and titles of the rendered wiki pages, they are parsed and
+* validated once when the mapping is loaded and shared by every worker
+* */
+#[derive(Debug, Clone)]
+pub struct ScraperSelectors {
+    pub(super) galleries: GallerySelectors,
+    pub(super) infobox: InfoboxSelectors,
+    pub(super) images: ImageSelectors,
+    pub(super) abilities: AbilitySelectors,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct GallerySelectors {
+    pub item: Selector,
+    pub link: Selector,
+    pub devils: Vec<Gallery>,
+    pub characters: Vec<Gallery>,
+}
+
+/// Gallery div of a category
+#[derive(Debug, Clone)]
+pub(super) struct Gallery {
+    pub category: String,
+    pub id: String,
+    pub root: Selector,
+    /// Position of the gallery among the galleries of the page, MediaWiki renders the n-th
+    /// gallery with the id `gallery-n`
+    pub position: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct InfoboxSelectors {
+    pub section: Selector,
+    pub section_title: Selector,
+    pub name_section: String,
+    pub biological_section: String,
+    pub professional_section: String,
+    pub romaji_name: Selector,
+    pub nested_item: Selector,
+    pub link: Selector,
+    pub kanji: InfoboxField,
+    pub romaji: InfoboxField,
+    pub gender: InfoboxField,
+    pub birthplace: InfoboxField,
+    pub status: InfoboxField,
+    pub occupation: InfoboxField,
+    pub affiliation: InfoboxField,
+    pub contracts: InfoboxField,
+    pub relatives: InfoboxField,
+}
+
+/// Row of an infobox field, the source is the `data-source` of the row
+#[derive(Debug, Clone)]
+pub(super) struct InfoboxField {
+    pub source: String,
+    pub row: Selector,
+    pub value: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ImageSelectors {
+    pub figure: Selector,
+    pub collection: Selector,
+    pub tab_label: Selector,
+    pub tab_content: Selector,
+    pub tab_figure: Selector,
+    pub link: Selector,
+    pub thumbnail: Selector,
+    pub caption: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct AbilitySelectors {
+    pub headline: Selector,
+}
+
+#[derive(Debug)]
+pub enum SelectorsError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Unversioned,
+    UnsupportedVersion(i64),
+    InvalidSelector { key: String, selector: String },
+    Empty(String),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SelectorsFile {
+    galleries: GalleriesFile,
+    infobox: InfoboxFile,
+    images: ImagesFile,
+    abilities: AbilitiesFile,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GalleriesFile {
+    item: String,
+    link: String,
+    devils: Vec<GalleryFile>,
+    characters: Vec<GalleryFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GalleryFile {
+    category: String,
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InfoboxFile {
+    section: String,
+    section_title: String,
+    name_section: String,
+    biological_section: String,
+    professional_section: String,
+    romaji_name: String,
+    nested_item: String,
+    link: String,
+    fields: InfoboxFieldsFile,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InfoboxFieldsFile {
+    kanji: String,
+    romaji: String,
+    gender: String,
+    birthplace: String,
+    status: String,
+    occupation: String,
+    affiliation: String,
+    contracts: String,
+    relatives: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ImagesFile {
+    figure: String,
+    collection: String,
+    tab_label: String,
+    tab_content: String,
+    tab_figure: String,
+    link: String,
+    thumbnail: String,
+    caption: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AbilitiesFile {
+    headline: String,
+}
+
+impl ScraperSelectors {
+    /// Selectors of the mapping shipped with the scraper, compiled once
+    pub fn builtin() -> Arc<Self> {
+        BUILTIN_SELECTORS.clone()
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SelectorsError> {
+        let toml = fs::read_to_string(path).map_err(SelectorsError::Io)?;
+        Self::from_toml(&toml)
+    }
+
+    /// Parses and compiles a mapping, the version is checked before the keys so that a mapping
+    /// of another version is rejected with a clear error instead of a missing key
+    pub fn from_toml(toml: &str) -> Result<Self, SelectorsError> {
+        let mut table: toml::value::Table = toml::from_str(toml).map_err(SelectorsError::Toml)?;
+
+        let version = match table.remove("version") {
+            Some(version) => version
+                .as_integer()
+                .ok_or(SelectorsError::UnsupportedVersion(0))?,
+            None => return Err(SelectorsError::Unversioned),
+        };
+        if version != SELECTORS_SCHEMA_VERSION as i64 {
+            return Err(SelectorsError::UnsupportedVersion(version));
+        }
+
+        let file: SelectorsFile = toml::Value::Table(table)
+            .try_into()
+            .map_err(SelectorsError::Toml)?;
+        let infobox = file.infobox;
+        let fields = infobox.fields;
+        let images = file.images;
+
+        Ok(ScraperSelectors {
+            galleries: GallerySelectors {
+                item: compile("galleries.item", &file.galleries.item)?,
+                link: compile("galleries.link", &file.galleries.link)?,
+                devils: compile_galleries("galleries.devils", file.galleries.devils)?,
+                characters: compile_galleries("galleries.characters", file.galleries.characters)?,
+            },
+            infobox: InfoboxSelectors {
+                section: compile("infobox.section", &infobox.section)?,
+                section_title: compile("infobox.section_title", &infobox.section_title)?,
+                name_section: non_empty("infobox.name_section", infobox.name_section)?,
+                biological_section: non_empty(
+                    "infobox.biological_section",
+                    infobox.biological_section,
+                )?,
+                professional_section: non_empty(
+                    "infobox.professional_section",
+                    infobox.professional_section,
+                )?,
+                romaji_name: compile("infobox.romaji_name", &infobox.romaji_name)?,
+                nested_item: compile("infobox.nested_item", &infobox.nested_item)?,
+                link: compile("infobox.link", &infobox.link)?,
+                kanji: compile_field("infobox.fields.kanji", fields.kanji)?,
+                romaji: compile_field("infobox.fields.romaji", fields.romaji)?,
+                gender: compile_field("infobox.fields.gender", fields.gender)?,
+                birthplace: compile_field("infobox.fields.birthplace", fields.birthplace)?,
+                status: compile_field("infobox.fields.status", fields.status)?,
+                occupation: compile_field("infobox.fields.occupation", fields.occupation)?,
+                affiliation: compile_field("infobox.fields.affiliation", fields.affiliation)?,
+                contracts: compile_field("infobox.fields.contracts", fields.contracts)?,
+                relatives: compile_field("infobox.fields.relatives", fields.relatives)?,
+            },
+            images: ImageSelectors {
+                figure: compile("images.figure", &images.figure)?,
+                collection: compile("images.collection", &images.collection)?,
+                tab_label: compile("images.tab_label", &images.tab_label)?,
+                tab_content: compile("images.tab_content", &images.tab_content)?,
+                tab_figure: compile("images.tab_figure", &images.tab_figure)?,
+                link: compile("images.link", &images.link)?,
+                thumbnail: compile("images.thumbnail", &images.thumbnail)?,
+                caption: compile("images.caption", &images.caption)?,
+            },
+            abilities: AbilitySelectors {
+                headline: compile("abilities.headline", &file.abilities.headline)?,
+            },
+        })
+    }
+}
+
+fn compile(key: &str, selector: &str) -> Result<Selector, SelectorsError> {
+    Selector::parse(selector).map_err(|_| SelectorsError::InvalidSelector {
+        key: key.to_string(),
+        selector: selector.to_string(),
+    })
+}
+
+fn non_empty(key: &str, value: String) -> Result<String, SelectorsError> {
+    if value.trim().is_empty() {
+        return Err(SelectorsError::Empty(key.to_string()));
+    }
+
+    Ok(value)
+}
+
+fn compile_galleries(
+    key: &str,
+    galleries: Vec<GalleryFile>,
+) -> Result<Vec<Gallery>, SelectorsError> {
+    if galleries.is_empty() {
+        return Err(SelectorsError::Empty(key.to_string()));
+    }
+
+    galleries
+        .into_iter()
+        .map(|gallery| {
+            let id = non_empty(&format!("{}.id", key), gallery.id)?;
+            Ok(Gallery {
+                category: non_empty(&format!("{}.category", key), gallery.category)?,
+                root: compile(&format!("{}.id", key), &format!(r#"div[id="{}"]"#, id))?,
+                position: id
+                    .strip_prefix("gallery-")
+                    .and_then(|position| position.parse().ok()),
+                id,
+            })
+        })
+        .collect()
+}
+
+fn compile_field(key: &str, source: String) -> Result<InfoboxField, SelectorsError> {
+    let source = non_empty(key, source)?;
+    let row = format!(r#"div[data-source="{}"]"#, source);
+
+    Ok(InfoboxField {
+        value: compile(key, &format!("{} > div", row))?,
+        row: compile(key, &row)?,
+        source,
+    })
+}
+
+impl fmt::Display for SelectorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SelectorsError::Io(ref err) => write!(f, "Unable to read the selectors: {}", err),
+            SelectorsError::Toml(ref err) => write!(f, "The selectors are invalid: {}", err),
+            SelectorsError::Unversioned => write!(f, "The selectors have no version"),
+            SelectorsError::UnsupportedVersion(version) => write!(
+                f,
+                "The selectors have the version {} but version {} is expected",
+                version, SELECTORS_SCHEMA_VERSION
+            ),
+            SelectorsError::InvalidSelector {
+                ref key,
+                ref selector,
+            } => write!(f, "Selector {} of {} is invalid", selector, key),
+            SelectorsError::Empty(ref key) => write!(f, "{} of the selectors is empty", key),
+        }
+    }
+}
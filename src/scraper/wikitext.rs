@@ -0,0 +1,331 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref REF_MATCHER: Regex = Regex::new(r#"(?is)<ref[^>/]*/>|<ref[^>]*>.*?</ref>"#).unwrap();
+    static ref COMMENT_MATCHER: Regex = Regex::new(r#"(?s)<!--.*?-->"#).unwrap();
+    static ref TEMPLATE_MATCHER: Regex = Regex::new(r#"\{\{[^{}]*\}\}"#).unwrap();
+    static ref FILE_LINK_MATCHER: Regex =
+        Regex::new(r#"(?i)\[\[(?:file|image):[^\]]*\]\]"#).unwrap();
+    static ref LINK_MATCHER: Regex = Regex::new(r#"\[\[([^|\]]*)(?:\|([^\]]*))?\]\]"#).unwrap();
+    static ref EXTERNAL_LINK_MATCHER: Regex =
+        Regex::new(r#"\[https?://[^\s\]]+ ?([^\]]*)\]"#).unwrap();
+    static ref TAG_MATCHER: Regex = Regex::new(r#"<[^>]+>"#).unwrap();
+    static ref LINE_BREAK_MATCHER: Regex = Regex::new(r#"(?i)<br\s*/?>|\n"#).unwrap();
+    static ref ITALIC_MATCHER: Regex = Regex::new(r#"''([^']+)''"#).unwrap();
+    static ref HEADING_MATCHER: Regex = Regex::new(r#"^(={2,6})\s*(.+?)\s*(={2,6})\s*$"#).unwrap();
+    static ref GALLERY_MATCHER: Regex =
+        Regex::new(r#"(?is)<gallery[^>]*>(.*?)</gallery>"#).unwrap();
+    static ref RUBY_MATCHER: Regex = Regex::new(r#"(?is)<ruby[^>]*>(.*?)</ruby>"#).unwrap();
+    static ref RUBY_TEMPLATE_MATCHER: Regex =
+        Regex::new(r#"(?i)\{\{\s*(?:ruby|furigana)\s*\|([^|{}]*)\|([^|{}]*)\}\}"#).unwrap();
+    static ref RUBY_TEXT_MATCHER: Regex = Regex::new(r#"(?is)<rt[^>]*>(.*?)</rt>"#).unwrap();
+    static ref RUBY_PARENTHESIS_MATCHER: Regex = Regex::new(r#"(?is)<rp[^>]*>.*?</rp>"#).unwrap();
+    static ref TABBER_MATCHER: Regex = Regex::new(r#"(?is)<tabber[^>]*>(.*?)</tabber>"#).unwrap();
+}
+
+/// Tags whose content is skipped when looking for the `|` and `}}` of a template
+const OPAQUE_TAGS: [&str; 4] = ["gallery", "tabber", "ref", "nowiki"];
+
+/// A `{{Name|key=value|...}}` template call, positional parameters are keyed by their position
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl Template {
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub level: usize,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Link {
+    pub target: String,
+    pub label: String,
+}
+
+/// Finds the first template whose name matches the predicate
+pub fn find_template<P: Fn(&str) -> bool>(text: &str, predicate: P) -> Option<Template> {
+    let mut from = 0;
+
+    while let Some(offset) = text[from..].find("{{") {
+        let start = from + offset;
+        let end = closing_braces(text, start)?;
+
+        let parts = split_top_level(&text[start + 2..end]);
+        let name = parts.first().map(|name| name.trim()).unwrap_or_default();
+        if predicate(name) {
+            let params = parts[1..]
+                .iter()
+                .enumerate()
+                .map(|(position, param)| match param.split_once('=') {
+                    Some((key, value)) if !key.contains(['[', '{', '<']) => {
+                        (key.trim().to_string(), value.trim().to_string())
+                    }
+                    _ => ((position + 1).to_string(), param.trim().to_string()),
+                })
+                .collect();
+
+            return Some(Template {
+                name: name.to_string(),
+                params,
+            });
+        }
+
+        from = start + 2;
+    }
+
+    None
+}
+
+/// Splits the text into its sections, the text before the first heading is left out
+pub fn sections(text: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for line in text.lines() {
+        if let Some(groups) = HEADING_MATCHER.captures(line.trim()) {
+            sections.push(Section {
+                level: groups[1].len().min(groups[3].len()),
+                title: plain_text(&groups[2]),
+                body: String::new(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.body.push_str(line);
+            section.body.push('\n');
+        }
+    }
+
+    sections
+}
+
+/// Content lines of every `<gallery>` tag, in the order the galleries appear
+pub fn galleries(text: &str) -> Vec<Vec<String>> {
+    GALLERY_MATCHER
+        .captures_iter(text)
+        .map(|groups| {
+            groups[1]
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect()
+        })
+        .collect()
+}
+
+/// Tabs of every `<tabber>` tag as `(title, content)`, tabs being separated by `|-|`
+pub fn tabs(text: &str) -> Vec<(String, String)> {
+    TABBER_MATCHER
+        .captures_iter(text)
+        .flat_map(|groups| {
+            groups[1]
+                .split("|-|")
+                .filter_map(|tab| tab.split_once('='))
+                .map(|(title, content)| (title.trim().to_string(), content.trim().to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Internal links of the text, files and images included, links of references are left out
+pub fn links(text: &str) -> Vec<Link> {
+    let text = REF_MATCHER.replace_all(text, "");
+    let text = COMMENT_MATCHER.replace_all(&text, "");
+
+    LINK_MATCHER
+        .captures_iter(&text)
+        .map(|groups| {
+            let target = groups[1].trim().to_string();
+            let label = match groups.get(2) {
+                Some(label) => plain_text(label.as_str()),
+                None => target.clone(),
+            };
+
+            Link { target, label }
+        })
+        .collect()
+}
+
+/// Italic segments of the text, bold markup is ignored
+pub fn italics(text: &str) -> Vec<String> {
+    let text = REF_MATCHER.replace_all(text, "").replace("'''", "");
+
+    ITALIC_MATCHER
+        .captures_iter(&text)
+        .map(|groups| plain_text(&groups[1]))
+        .filter(|italic| !italic.is_empty())
+        .collect()
+}
+
+/// Strips the markup of the text, keeping the labels of its links
+pub fn plain_text(text: &str) -> String {
+    let text = REF_MATCHER.replace_all(text, "");
+    let mut text = COMMENT_MATCHER.replace_all(&text, "").to_string();
+
+    // Templates are removed from the innermost one outwards
+    while TEMPLATE_MATCHER.is_match(&text) {
+        text = TEMPLATE_MATCHER.replace_all(&text, "").to_string();
+    }
+
+    let text = FILE_LINK_MATCHER.replace_all(&text, "");
+    let text = LINK_MATCHER.replace_all(&text, |groups: &regex::Captures| {
+        groups
+            .get(2)
+            .unwrap_or_else(|| groups.get(1).unwrap())
+            .as_str()
+            .to_string()
+    });
+    let text = EXTERNAL_LINK_MATCHER.replace_all(&text, "$1");
+    let text = TAG_MATCHER.replace_all(&text, "");
+
+    text.replace("'''", "")
+        .replace("''", "")
+        .replace("&nbsp;", " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Plain text of a name annotated with furigana, as `<ruby>` tags or `{{Ruby|base|reading}}`
+/// templates, and its reading when it has an annotation. The reading is the text with every
+/// annotated base replaced by its furigana
+pub fn ruby_text(text: &str) -> (String, Option<String>) {
+    let mut has_ruby = false;
+    let mut base = text.to_string();
+    let mut reading = text.to_string();
+
+    for groups in RUBY_MATCHER.captures_iter(text) {
+        has_ruby = true;
+        let ruby = RUBY_PARENTHESIS_MATCHER.replace_all(&groups[1], "");
+        let furigana = RUBY_TEXT_MATCHER
+            .captures_iter(&ruby)
+            .map(|groups| groups[1].to_string())
+            .collect::<String>();
+
+        base = base.replacen(&groups[0], &RUBY_TEXT_MATCHER.replace_all(&ruby, ""), 1);
+        reading = reading.replacen(&groups[0], &furigana, 1);
+    }
+    for groups in RUBY_TEMPLATE_MATCHER.captures_iter(text) {
+        has_ruby = true;
+        base = base.replacen(&groups[0], groups[1].trim(), 1);
+        reading = reading.replacen(&groups[0], groups[2].trim(), 1);
+    }
+
+    let reading = Some(plain_text(&reading)).filter(|reading| has_ruby && !reading.is_empty());
+    (plain_text(&base), reading)
+}
+
+/// Splits the text on line breaks and list bullets into its non empty lines, the markup of
+/// every line is kept
+pub fn raw_lines(text: &str) -> Vec<String> {
+    LINE_BREAK_MATCHER
+        .split(text)
+        .map(|line| line.trim().trim_start_matches(['*', '#', ':']).trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Splits the text on line breaks and list bullets into its non empty plain text lines
+pub fn lines(text: &str) -> Vec<String> {
+    raw_lines(text)
+        .iter()
+        .map(|line| plain_text(line))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Position of the `}}` closing the template opened at `start`
+fn closing_braces(text: &str, start: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+
+    while i < bytes.len() {
+        if let Some(end) = skip_opaque_tag(text, i) {
+            i = end;
+        } else if bytes[i..].starts_with(b"{{") {
+            depth += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"}}") {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+
+    None
+}
+
+/// Splits the inside of a template on the `|` that aren't nested in a link, template or tag
+fn split_top_level(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut parts: Vec<&str> = Vec::new();
+    let mut depth = 0;
+    let mut from = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if let Some(end) = skip_opaque_tag(text, i) {
+            i = end;
+        } else if bytes[i..].starts_with(b"{{") || bytes[i..].starts_with(b"[[") {
+            depth += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"}}") || bytes[i..].starts_with(b"]]") {
+            depth -= 1;
+            i += 2;
+        } else {
+            if bytes[i] == b'|' && depth == 0 {
+                parts.push(&text[from..i]);
+                from = i + 1;
+            }
+            i += 1;
+        }
+    }
+    parts.push(&text[from..]);
+
+    parts
+}
+
+/// End of the opaque tag starting at `i`, if any
+fn skip_opaque_tag(text: &str, i: usize) -> Option<usize> {
+    // `i` may be in the middle of a multibyte character, which can't be the start of a tag
+    if text.as_bytes().get(i) != Some(&b'<') {
+        return None;
+    }
+    let rest = &text[i..];
+
+    let tag = OPAQUE_TAGS.iter().find(|tag| {
+        rest[1..]
+            .get(..tag.len())
+            .map(|name| name.eq_ignore_ascii_case(tag))
+            .unwrap_or(false)
+    })?;
+
+    let open_end = rest.find('>')?;
+    if rest[..open_end].ends_with('/') {
+        return Some(i + open_end + 1);
+    }
+
+    let closing = format!("</{}>", tag);
+    match rest.find(&closing) {
+        Some(close) => Some(i + close + closing.len()),
+        None => Some(text.len()),
+    }
+}
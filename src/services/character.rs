@@ -0,0 +1,121 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    models::{
+        Character, CharacterDetail, Dataset, EntityLinks, Revision, ScrapeReport, ScrapeResult,
+    },
+    scraper::ScrapeError,
+};
+
+use super::{scrape::scrape_pages, ServiceError};
+
+#[async_trait]
+pub trait CharacterDataSource: Send + Sync + 'static {
+    /// Scrapes the details of every given character, a character that fails to be scraped
+    /// is recorded in the report instead of failing the whole scrape
+    async fn scrape_character_details(
+        &self,
+        characters: &[Character],
+    ) -> Result<ScrapeResult<CharacterDetail>, ScrapeError>;
+    async fn scrape_characters(&self) -> Result<Vec<Character>, ScrapeError>;
+    async fn scrape_character_detail(
+        &self,
+        character: &Character,
+    ) -> Result<CharacterDetail, ScrapeError>;
+    /// Latest revision of the wiki page of every given character keyed by the id of the
+    /// character, a character whose page doesn't exist is left out
+    async fn scrape_character_revisions(
+        &self,
+        characters: &[Character],
+    ) -> Result<HashMap<String, Revision>, ScrapeError>;
+}
+
+#[async_trait]
+pub trait CharacterRepository: Send + Sync + 'static {
+    /// Replaces the stored characters with the given characters and their details
+    async fn save_characters(
+        &self,
+        characters: &[Character],
+        details: &[CharacterDetail],
+    ) -> Result<(), std::io::Error>;
+    async fn find_characters(&self) -> Result<Vec<Character>, std::io::Error>;
+    async fn find_character_detail(
+        &self,
+        id: &str,
+    ) -> Result<Option<CharacterDetail>, std::io::Error>;
+    /// Resolved contracts and relatives of every stored character keyed by its id, read at once
+    async fn find_character_links(&self) -> Result<HashMap<String, EntityLinks>, std::io::Error>;
+    /// Revisions the stored details were scraped from, keyed by the id of the character
+    async fn find_character_revisions(&self) -> Result<HashMap<String, Revision>, std::io::Error>;
+    /// The reports of the characters are kept apart from the reports of the devils
+    async fn save_character_report(&self, report: &ScrapeReport) -> Result<(), std::io::Error>;
+    async fn find_latest_character_report(&self) -> Result<Option<ScrapeReport>, std::io::Error>;
+}
+
+pub struct CharacterService {
+    scraper: Option<Arc<dyn CharacterDataSource>>,
+    repository: Arc<dyn CharacterRepository>,
+}
+
+impl CharacterService {
+    pub fn new(
+        scraper: Arc<dyn CharacterDataSource>,
+        repository: Arc<dyn CharacterRepository>,
+    ) -> Self {
+        Self {
+            scraper: Some(scraper),
+            repository,
+        }
+    }
+
+    /// Service that only reads the repository, scraping with it fails
+    pub fn read_only(repository: Arc<dyn CharacterRepository>) -> Self {
+        Self {
+            scraper: None,
+            repository,
+        }
+    }
+
+    /// Scrapes the characters from the data source and stores them in the repository, the
+    /// same way `DevilService::scrape` does for the devils
+    pub async fn scrape(&self, full: bool) -> Result<ScrapeResult<CharacterDetail>, ServiceError> {
+        let scraper = self.scraper.as_ref().ok_or(ServiceError::NoDataSource)?;
+        let result = scrape_pages(
+            scraper.as_ref(),
+            self.repository.as_ref(),
+            full,
+            "characters",
+        )
+        .await?;
+        self.repository
+            .save_character_report(&result.report)
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Replaces the stored characters with the entries of a dataset written by the scraper
+    pub async fn load(&self, dataset: &Dataset<CharacterDetail>) -> Result<(), std::io::Error> {
+        let characters: Vec<Character> = dataset.entries.iter().map(Character::from).collect();
+        self.repository
+            .save_characters(&characters, &dataset.entries)
+            .await
+    }
+
+    pub async fn get_characters(&self) -> Result<Vec<Character>, std::io::Error> {
+        self.repository.find_characters().await
+    }
+
+    pub async fn get_character_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<CharacterDetail>, std::io::Error> {
+        self.repository.find_character_detail(id).await
+    }
+
+    pub async fn get_latest_report(&self) -> Result<Option<ScrapeReport>, std::io::Error> {
+        self.repository.find_latest_character_report().await
+    }
+}
@@ -1,20 +1,91 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
 
-use crate::models::DevilDetail;
+use crate::{
+    models::{Dataset, Devil, DevilDetail, EntityLinks, Revision, ScrapeReport, ScrapeResult},
+    scraper::ScrapeError,
+};
+
+use super::{scrape::scrape_pages, ServiceError};
 
 #[async_trait]
 pub trait DevilDataSource: Send + Sync + 'static {
-    async fn scrape(&self) -> Result<Vec<DevilDetail>, std::io::Error>;
+    /// Scrapes the details of every given devil, a devil that fails to be scraped is
+    /// recorded in the report instead of failing the whole scrape
+    async fn scrape(&self, devils: &[Devil]) -> Result<ScrapeResult, ScrapeError>;
+    async fn scrape_devils(&self) -> Result<Vec<Devil>, ScrapeError>;
+    async fn scrape_devil_detail(&self, devil: &Devil) -> Result<DevilDetail, ScrapeError>;
+    /// Latest revision of the wiki page of every given devil keyed by the id of the devil,
+    /// a devil whose page doesn't exist is left out
+    async fn scrape_revisions(
+        &self,
+        devils: &[Devil],
+    ) -> Result<HashMap<String, Revision>, ScrapeError>;
+}
+
+#[async_trait]
+pub trait DevilRepository: Send + Sync + 'static {
+    /// Replaces the stored dataset with the given devils and their details
+    async fn save(&self, devils: &[Devil], details: &[DevilDetail]) -> Result<(), std::io::Error>;
+    async fn find_devils(&self) -> Result<Vec<Devil>, std::io::Error>;
+    async fn find_devil_detail(&self, id: &str) -> Result<Option<DevilDetail>, std::io::Error>;
+    /// Resolved contracts and relatives of every stored devil keyed by its id, read at once
+    async fn find_devil_links(&self) -> Result<HashMap<String, EntityLinks>, std::io::Error>;
+    /// Revisions the stored details were scraped from, keyed by the id of the devil
+    async fn find_revisions(&self) -> Result<HashMap<String, Revision>, std::io::Error>;
+    async fn save_report(&self, report: &ScrapeReport) -> Result<(), std::io::Error>;
+    async fn find_latest_report(&self) -> Result<Option<ScrapeReport>, std::io::Error>;
 }
 
 pub struct DevilService {
-    scraper: Arc<dyn DevilDataSource>,
+    scraper: Option<Arc<dyn DevilDataSource>>,
+    repository: Arc<dyn DevilRepository>,
 }
 
 impl DevilService {
-    pub fn new(scraper: Arc<dyn DevilDataSource>) -> Self {
-        Self { scraper }
+    pub fn new(scraper: Arc<dyn DevilDataSource>, repository: Arc<dyn DevilRepository>) -> Self {
+        Self {
+            scraper: Some(scraper),
+            repository,
+        }
+    }
+
+    /// Service that only reads the repository, scraping with it fails
+    pub fn read_only(repository: Arc<dyn DevilRepository>) -> Self {
+        Self {
+            scraper: None,
+            repository,
+        }
+    }
+
+    /// Scrapes the devils from the data source and stores them in the repository. Only the
+    /// devils whose page changed since the last scrape are scraped again unless `full` is set,
+    /// the others keep their stored detail
+    pub async fn scrape(&self, full: bool) -> Result<ScrapeResult, ServiceError> {
+        let scraper = self.scraper.as_ref().ok_or(ServiceError::NoDataSource)?;
+        let result =
+            scrape_pages(scraper.as_ref(), self.repository.as_ref(), full, "devils").await?;
+        self.repository.save_report(&result.report).await?;
+
+        Ok(result)
+    }
+
+    /// Replaces the stored devils with the entries of a dataset written by the scraper
+    pub async fn load(&self, dataset: &Dataset<DevilDetail>) -> Result<(), std::io::Error> {
+        let devils: Vec<Devil> = dataset.entries.iter().map(Devil::from).collect();
+        self.repository.save(&devils, &dataset.entries).await
+    }
+
+    pub async fn get_devils(&self) -> Result<Vec<Devil>, std::io::Error> {
+        self.repository.find_devils().await
+    }
+
+    pub async fn get_devil_by_id(&self, id: &str) -> Result<Option<DevilDetail>, std::io::Error> {
+        self.repository.find_devil_detail(id).await
+    }
+
+    pub async fn get_latest_report(&self) -> Result<Option<ScrapeReport>, std::io::Error> {
+        self.repository.find_latest_report().await
     }
 }
@@ -0,0 +1,33 @@
+use std::{fmt, io};
+
+use crate::scraper::ScrapeError;
+
+#[derive(Debug)]
+pub enum ServiceError {
+    Scrape(ScrapeError),
+    Repository(io::Error),
+    /// The service was built without a data source and can only read the repository
+    NoDataSource,
+}
+
+impl From<ScrapeError> for ServiceError {
+    fn from(err: ScrapeError) -> Self {
+        ServiceError::Scrape(err)
+    }
+}
+
+impl From<io::Error> for ServiceError {
+    fn from(err: io::Error) -> Self {
+        ServiceError::Repository(err)
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ServiceError::Scrape(ref err) => err.fmt(f),
+            ServiceError::Repository(ref err) => err.fmt(f),
+            ServiceError::NoDataSource => write!(f, "The service has no data source to scrape"),
+        }
+    }
+}
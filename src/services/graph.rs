@@ -0,0 +1,129 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crate::models::{
+    EdgeKind, EntityLink, Graph, GraphEdge, GraphNode, GraphPath, Neighbor, NodeKind,
+};
+
+use super::{CharacterRepository, DevilRepository};
+
+pub struct GraphService {
+    devil_repository: Arc<dyn DevilRepository>,
+    character_repository: Arc<dyn CharacterRepository>,
+}
+
+impl GraphService {
+    pub fn new(
+        devil_repository: Arc<dyn DevilRepository>,
+        character_repository: Arc<dyn CharacterRepository>,
+    ) -> Self {
+        Self {
+            devil_repository,
+            character_repository,
+        }
+    }
+
+    /// Relationship graph of the stored devils and characters with only the edges of the
+    /// kinds, every kind is kept when `kinds` is empty
+    pub async fn get_graph(&self, kinds: &[EdgeKind]) -> Result<Graph, std::io::Error> {
+        let mut graph = self.build_graph().await?;
+        graph
+            .edges
+            .retain(|edge| kinds.is_empty() || kinds.contains(&edge.kind));
+
+        Ok(graph)
+    }
+
+    pub async fn get_neighbors(
+        &self,
+        id: &str,
+        kinds: &[EdgeKind],
+    ) -> Result<Option<Vec<Neighbor>>, std::io::Error> {
+        Ok(self.build_graph().await?.neighbors(id, kinds))
+    }
+
+    pub async fn get_shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        kinds: &[EdgeKind],
+    ) -> Result<Option<GraphPath>, std::io::Error> {
+        Ok(self.build_graph().await?.shortest_path(from, to, kinds))
+    }
+
+    pub async fn get_components(&self, kinds: &[EdgeKind]) -> Result<Vec<Graph>, std::io::Error> {
+        Ok(self.build_graph().await?.components(kinds))
+    }
+
+    /// Builds the relationship graph from the contracts and relatives of the stored devils
+    /// and characters, a page listed both as a devil and a character is a devil
+    async fn build_graph(&self) -> Result<Graph, std::io::Error> {
+        let mut graph = Graph::default();
+        let mut ids: HashSet<String> = HashSet::new();
+
+        // The links are read at once, reading every detail would read each entity in full
+        let devil_links = self.devil_repository.find_devil_links().await?;
+        for devil in self.devil_repository.find_devils().await? {
+            if !ids.insert(devil.id.clone()) {
+                continue;
+            }
+
+            if let Some(links) = devil_links.get(&devil.id) {
+                add_edges(&mut graph, &devil.id, EdgeKind::Contract, &links.contracts);
+                add_edges(&mut graph, &devil.id, EdgeKind::Relative, &links.relatives);
+            }
+            graph.nodes.push(GraphNode {
+                id: devil.id,
+                name: devil.devil_name,
+                kind: NodeKind::Devil,
+                wiki_url: devil.wiki_url,
+            });
+        }
+
+        let character_links = self.character_repository.find_character_links().await?;
+        for character in self.character_repository.find_characters().await? {
+            if !ids.insert(character.id.clone()) {
+                continue;
+            }
+
+            if let Some(links) = character_links.get(&character.id) {
+                add_edges(
+                    &mut graph,
+                    &character.id,
+                    EdgeKind::Contract,
+                    &links.contracts,
+                );
+                add_edges(
+                    &mut graph,
+                    &character.id,
+                    EdgeKind::Relative,
+                    &links.relatives,
+                );
+            }
+            graph.nodes.push(GraphNode {
+                id: character.id,
+                name: character.name,
+                kind: NodeKind::Character,
+                wiki_url: character.wiki_url,
+            });
+        }
+
+        Ok(graph)
+    }
+}
+
+/// Adds an edge for every link resolved to another entity of the dataset
+fn add_edges(graph: &mut Graph, source: &str, kind: EdgeKind, links: &[EntityLink]) {
+    for link in links {
+        let target = match &link.entity_id {
+            Some(target) if target != source => target,
+            _ => continue,
+        };
+
+        graph.edges.push(GraphEdge {
+            source: source.to_string(),
+            target: target.clone(),
+            kind,
+            qualifier: link.qualifier.clone(),
+        });
+    }
+}
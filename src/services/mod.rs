@@ -1,3 +1,10 @@
+pub mod character;
 pub mod devil;
+pub mod errors;
+pub mod graph;
+mod scrape;
 
+pub use character::*;
 pub use devil::*;
+pub use errors::*;
+pub use graph::*;
@@ -0,0 +1,233 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+
+use async_trait::async_trait;
+use duplicate::duplicate_item;
+
+use crate::{
+    models::{Character, CharacterDetail, Devil, DevilDetail, Revision, ScrapeResult},
+    scraper::ScrapeError,
+};
+
+use super::{
+    CharacterDataSource, CharacterRepository, DevilDataSource, DevilRepository, ServiceError,
+};
+
+/// Listed page of an entity of the wiki, e.g. a `Devil`
+pub(super) trait Page: Clone + Send + Sync {
+    fn id(&self) -> &str;
+}
+
+/// Scraped detail of a listed page, e.g. a `DevilDetail`
+pub(super) trait PageDetail: Send + Sync {
+    fn id(&self) -> &str;
+    fn set_revision(&mut self, revision: &Revision);
+}
+
+/// Data source of the pages of one entity, seen the same way by `scrape_pages`
+#[async_trait]
+pub(super) trait PageSource<P: Page, D: PageDetail>: Send + Sync {
+    async fn list_pages(&self) -> Result<Vec<P>, ScrapeError>;
+    async fn page_revisions(&self, pages: &[P]) -> Result<HashMap<String, Revision>, ScrapeError>;
+    async fn scrape_pages(&self, pages: &[P]) -> Result<ScrapeResult<D>, ScrapeError>;
+}
+
+/// Repository of the pages of one entity, seen the same way by `scrape_pages`
+#[async_trait]
+pub(super) trait PageStore<P: Page, D: PageDetail>: Send + Sync {
+    async fn find_page_revisions(&self) -> Result<HashMap<String, Revision>, io::Error>;
+    async fn find_page_detail(&self, id: &str) -> Result<Option<D>, io::Error>;
+    async fn save_pages(&self, pages: &[P], details: &[D]) -> Result<(), io::Error>;
+}
+
+#[duplicate_item(page_type; [Devil]; [Character])]
+impl Page for page_type {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[duplicate_item(detail_type; [DevilDetail]; [CharacterDetail])]
+impl PageDetail for detail_type {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_revision(&mut self, revision: &Revision) {
+        self.revision_id = Some(revision.id);
+        self.revision_timestamp = Some(revision.timestamp);
+    }
+}
+
+#[async_trait]
+impl<T: DevilDataSource + ?Sized> PageSource<Devil, DevilDetail> for T {
+    async fn list_pages(&self) -> Result<Vec<Devil>, ScrapeError> {
+        self.scrape_devils().await
+    }
+
+    async fn page_revisions(
+        &self,
+        pages: &[Devil],
+    ) -> Result<HashMap<String, Revision>, ScrapeError> {
+        self.scrape_revisions(pages).await
+    }
+
+    async fn scrape_pages(&self, pages: &[Devil]) -> Result<ScrapeResult, ScrapeError> {
+        self.scrape(pages).await
+    }
+}
+
+#[async_trait]
+impl<T: CharacterDataSource + ?Sized> PageSource<Character, CharacterDetail> for T {
+    async fn list_pages(&self) -> Result<Vec<Character>, ScrapeError> {
+        self.scrape_characters().await
+    }
+
+    async fn page_revisions(
+        &self,
+        pages: &[Character],
+    ) -> Result<HashMap<String, Revision>, ScrapeError> {
+        self.scrape_character_revisions(pages).await
+    }
+
+    async fn scrape_pages(
+        &self,
+        pages: &[Character],
+    ) -> Result<ScrapeResult<CharacterDetail>, ScrapeError> {
+        self.scrape_character_details(pages).await
+    }
+}
+
+#[async_trait]
+impl<T: DevilRepository + ?Sized> PageStore<Devil, DevilDetail> for T {
+    async fn find_page_revisions(&self) -> Result<HashMap<String, Revision>, io::Error> {
+        self.find_revisions().await
+    }
+
+    async fn find_page_detail(&self, id: &str) -> Result<Option<DevilDetail>, io::Error> {
+        self.find_devil_detail(id).await
+    }
+
+    async fn save_pages(&self, pages: &[Devil], details: &[DevilDetail]) -> Result<(), io::Error> {
+        self.save(pages, details).await
+    }
+}
+
+#[async_trait]
+impl<T: CharacterRepository + ?Sized> PageStore<Character, CharacterDetail> for T {
+    async fn find_page_revisions(&self) -> Result<HashMap<String, Revision>, io::Error> {
+        self.find_character_revisions().await
+    }
+
+    async fn find_page_detail(&self, id: &str) -> Result<Option<CharacterDetail>, io::Error> {
+        self.find_character_detail(id).await
+    }
+
+    async fn save_pages(
+        &self,
+        pages: &[Character],
+        details: &[CharacterDetail],
+    ) -> Result<(), io::Error> {
+        self.save_characters(pages, details).await
+    }
+}
+
+/// Scrapes the pages listed by the data source and stores them in the repository. Only the
+/// pages that changed since the last scrape are scraped again unless `full` is set, the others
+/// keep their stored detail. The entity names the pages in the logs, e.g. `devils`
+pub(super) async fn scrape_pages<P, D, S, R>(
+    source: &S,
+    store: &R,
+    full: bool,
+    entity: &str,
+) -> Result<ScrapeResult<D>, ServiceError>
+where
+    P: Page,
+    D: PageDetail,
+    S: PageSource<P, D> + ?Sized,
+    R: PageStore<P, D> + ?Sized,
+{
+    let mut pages = source.list_pages().await?;
+    // A page listed in several category galleries is only scraped once
+    let mut ids: HashSet<String> = HashSet::new();
+    pages.retain(|page| ids.insert(page.id().to_string()));
+
+    // The revisions are only needed to skip the unchanged pages, a page scraped without its
+    // revision is simply scraped again next time
+    let revisions = if full {
+        HashMap::new()
+    } else {
+        match source.page_revisions(&pages).await {
+            Ok(revisions) => revisions,
+            Err(err) => {
+                tracing::warn!(
+                    "Unable to fetch the revisions of the {}, every page is scraped again: {}",
+                    entity,
+                    err
+                );
+                HashMap::new()
+            }
+        }
+    };
+    let stored_revisions = if full {
+        HashMap::new()
+    } else {
+        store.find_page_revisions().await?
+    };
+
+    let mut changed: Vec<P> = Vec::new();
+    let mut unchanged: Vec<D> = Vec::new();
+    for page in &pages {
+        let is_unchanged = match (revisions.get(page.id()), stored_revisions.get(page.id())) {
+            (Some(latest), Some(stored)) => latest.id == stored.id,
+            _ => false,
+        };
+
+        let stored = if is_unchanged {
+            store.find_page_detail(page.id()).await?
+        } else {
+            None
+        };
+        match stored {
+            Some(detail) => unchanged.push(detail),
+            None => changed.push(page.clone()),
+        }
+    }
+
+    tracing::info!(
+        "{} {} changed since the last scrape, {} are unchanged",
+        changed.len(),
+        entity,
+        unchanged.len()
+    );
+
+    let mut result = source.scrape_pages(&changed).await?;
+    for detail in result.details.iter_mut() {
+        // The revision is fetched before the page, if the page is edited in between it is
+        // simply scraped again next time
+        if let Some(revision) = revisions.get(detail.id()) {
+            detail.set_revision(revision);
+        }
+    }
+
+    // A page that failed to be scraped keeps its stored detail until it is scraped again
+    if !full {
+        for failed in &result.report.failed {
+            if let Some(detail) = store.find_page_detail(&failed.id).await? {
+                result.details.push(detail);
+            }
+        }
+    }
+
+    result.report.unchanged = unchanged
+        .iter()
+        .map(|detail| detail.id().to_string())
+        .collect();
+    result.details.extend(unchanged);
+
+    store.save_pages(&pages, &result.details).await?;
+
+    Ok(result)
+}
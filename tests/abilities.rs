@@ -0,0 +1,144 @@
+use chainsawman_api::{models::Ability, scraper::scrape_ability_list};
+use proptest::prelude::*;
+use scraper::{Html, Selector};
+
+/// Name, description and sub-abilities of an ability, to compare ability trees
+#[derive(Debug, Clone, PartialEq)]
+struct Node(String, String, Vec<Node>);
+
+fn parse(html: &str) -> Vec<Ability> {
+    let fragment = Html::parse_fragment(html);
+    let selector = Selector::parse("ul").unwrap();
+    scrape_ability_list(fragment.select(&selector).next().unwrap())
+}
+
+fn nodes(abilities: &[Ability]) -> Vec<Node> {
+    abilities
+        .iter()
+        .map(|ability| {
+            Node(
+                ability.name.clone(),
+                ability.description.clone(),
+                nodes(&ability.abilities),
+            )
+        })
+        .collect()
+}
+
+fn node(name: &str, description: &str, abilities: Vec<Node>) -> Node {
+    Node(name.to_string(), description.to_string(), abilities)
+}
+
+/// Renders the abilities the way the wiki lists them, with a reference after every description
+fn render(abilities: &[Node]) -> String {
+    let mut html = String::from("<ul>\n");
+    for (index, Node(name, description, children)) in abilities.iter().enumerate() {
+        html.push_str(&format!("<li><b>{}</b>", name));
+        if !description.is_empty() {
+            html.push_str(&format!(
+                ": {}<sup id=\"cite_ref-{}\" class=\"reference\"><a href=\"#cite_note-{}\">[{}]</a></sup>",
+                description,
+                index + 1,
+                index + 1,
+                index + 1
+            ));
+        }
+        if !children.is_empty() {
+            html.push('\n');
+            html.push_str(&render(children));
+        }
+        html.push_str("</li>\n");
+    }
+    html.push_str("</ul>");
+    html
+}
+
+#[test]
+fn keeps_sub_abilities_out_of_the_parent_description() {
+    let abilities = parse(
+        r#"<ul>
+<li><b>Regeneration</b>: Heals by drinking blood.
+<ul>
+<li><b>Limb Regrowth</b>: Regrows lost limbs.</li>
+</ul>
+</li>
+</ul>"#,
+    );
+
+    assert_eq!(
+        nodes(&abilities),
+        vec![node(
+            "Regeneration",
+            "Heals by drinking blood.",
+            vec![node("Limb Regrowth", "Regrows lost limbs.", vec![])]
+        )]
+    );
+}
+
+#[test]
+fn keeps_colons_in_descriptions() {
+    let abilities =
+        parse("<ul><li><b>Sonic Scream</b>: Two effects: deafening and shattering.</li></ul>");
+
+    assert_eq!(abilities[0].name, "Sonic Scream");
+    assert_eq!(
+        abilities[0].description,
+        "Two effects: deafening and shattering."
+    );
+}
+
+#[test]
+fn strips_references_from_descriptions() {
+    let abilities = parse(
+        r##"<ul><li><b>Flight</b>: Flies using its wings.<sup class="reference"><a href="#cite_note-1">[1]</a></sup> Fast.[2]</li></ul>"##,
+    );
+
+    assert_eq!(abilities[0].description, "Flies using its wings. Fast.");
+}
+
+#[test]
+fn scrapes_abilities_without_a_description() {
+    let abilities = parse(
+        r#"<ul><li><b>Immortality</b>
+<ul><li>Resurrection</li></ul>
+</li></ul>"#,
+    );
+
+    assert_eq!(
+        nodes(&abilities),
+        vec![node(
+            "Immortality",
+            "",
+            vec![node("Resurrection", "", vec![])]
+        )]
+    );
+}
+
+fn text() -> impl Strategy<Value = String> {
+    prop::collection::vec("[A-Za-z]{1,8}", 1..4).prop_map(|words| words.join(" "))
+}
+
+fn description() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::new()),
+        text(),
+        (text(), text()).prop_map(|(left, right)| format!("{}: {}", left, right)),
+    ]
+}
+
+fn tree() -> impl Strategy<Value = Vec<Node>> {
+    let leaf =
+        (text(), description()).prop_map(|(name, description)| Node(name, description, vec![]));
+    let node = leaf.prop_recursive(4, 32, 4, |inner| {
+        (text(), description(), prop::collection::vec(inner, 0..4))
+            .prop_map(|(name, description, children)| Node(name, description, children))
+    });
+    prop::collection::vec(node, 1..4)
+}
+
+proptest! {
+    #[test]
+    fn parses_arbitrarily_nested_ability_lists(abilities in tree()) {
+        prop_assert_eq!(nodes(&parse(&render(&abilities))), abilities);
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers shared by the integration tests, every test file only uses some of them
+#![allow(dead_code)]
+
+use std::{collections::HashMap, path::PathBuf};
+
+use chainsawman_api::{
+    models::{Character, CharacterDetail, Devil, DevilCategory, DevilDetail, EntityLink},
+    services::DevilDataSource,
+};
+
+pub const WIKI_URL: &str = "https://chainsaw-man.fandom.com/wiki";
+
+/// Path of an empty database under the temporary directory of the tests
+pub fn database_path(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.db", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Link to the wiki page of an entity, or the plain name of an entity without a page
+pub fn link(name: &str, page: Option<&str>, qualifier: Option<&str>) -> EntityLink {
+    EntityLink {
+        name: name.to_string(),
+        wiki_url: page.map(|page| format!("{}/{}", WIKI_URL, page)),
+        entity_id: None,
+        qualifier: qualifier.map(|qualifier| qualifier.to_string()),
+    }
+}
+
+pub async fn find_devil<S: DevilDataSource + ?Sized>(scraper: &S, id: &str) -> Devil {
+    let devils = scraper.scrape_devils().await.unwrap();
+    devils.into_iter().find(|devil| devil.id == id).unwrap()
+}
+
+/// Devil whose wiki page is named after it
+pub fn devil(id: &str, name: &str, category: DevilCategory) -> Devil {
+    Devil {
+        id: id.to_string(),
+        devil_name: name.to_string(),
+        alias_name: None,
+        wiki_url: format!("{}/{}", WIKI_URL, name.replace(' ', "_")),
+        category,
+    }
+}
+
+/// Character whose wiki page is named after it
+pub fn character(id: &str, name: &str, category: &str) -> Character {
+    Character {
+        id: id.to_string(),
+        name: name.to_string(),
+        alias_name: None,
+        wiki_url: format!("{}/{}", WIKI_URL, name.replace(' ', "_")),
+        category: category.to_string(),
+    }
+}
+
+/// Detail of a devil with only the fields of its listing, the others are empty
+pub fn devil_detail(devil: &Devil) -> DevilDetail {
+    DevilDetail {
+        id: devil.id.clone(),
+        devil_name: devil.devil_name.clone(),
+        alias_name: devil.alias_name.clone(),
+        wiki_url: devil.wiki_url.clone(),
+        category: devil.category.clone(),
+        names: HashMap::new(),
+        image_src: None,
+        images: Vec::new(),
+        gender: None,
+        birthplace: None,
+        status: None,
+        occupations: Vec::new(),
+        affiliations: Vec::new(),
+        contracts: Vec::new(),
+        relatives: Vec::new(),
+        abilities: Vec::new(),
+        revision_id: None,
+        revision_timestamp: None,
+        warnings: Vec::new(),
+    }
+}
+
+/// Detail of a character with only the fields of its listing, the others are empty
+pub fn character_detail(character: &Character) -> CharacterDetail {
+    CharacterDetail {
+        id: character.id.clone(),
+        name: character.name.clone(),
+        alias_name: character.alias_name.clone(),
+        wiki_url: character.wiki_url.clone(),
+        category: character.category.clone(),
+        names: HashMap::new(),
+        image_src: None,
+        images: Vec::new(),
+        gender: None,
+        birthplace: None,
+        status: None,
+        occupations: Vec::new(),
+        affiliations: Vec::new(),
+        contracts: Vec::new(),
+        relatives: Vec::new(),
+        abilities: Vec::new(),
+        revision_id: None,
+        revision_timestamp: None,
+        warnings: Vec::new(),
+    }
+}
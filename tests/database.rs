@@ -0,0 +1,190 @@
+mod common;
+
+use std::sync::Arc;
+
+use chainsawman_api::{
+    database::SqliteDevilRepository,
+    models::{
+        Ability, AbilitySection, Character, CharacterDetail, DevilCategory, DevilName, EntityLink,
+        ParseWarning, Status,
+    },
+    services::{
+        CharacterRepository, CharacterService, DevilRepository, DevilService, ServiceError,
+    },
+};
+
+use common::{character, character_detail, database_path, devil, devil_detail};
+
+/// Detail of Aki with a value in every table of the details
+fn aki_detail() -> CharacterDetail {
+    let mut detail = character_detail(&character("aki-hayakawa", "Aki Hayakawa", "Public Safety"));
+    detail.names.insert(
+        "japanese".to_string(),
+        DevilName {
+            devil_name: "早川アキ".to_string(),
+            alias_name: None,
+            reading: Some("Hayakawa Aki".to_string()),
+            alias_reading: None,
+        },
+    );
+    detail.status = Some(Status::Deceased);
+    detail.occupations = vec!["Devil Hunter".to_string()];
+    detail.contracts = vec![EntityLink {
+        name: "Fox Devil".to_string(),
+        wiki_url: Some("https://chainsaw-man.fandom.com/wiki/fox-devil".to_string()),
+        entity_id: None,
+        qualifier: None,
+    }];
+    detail.abilities = vec![AbilitySection {
+        key: "devil".to_string(),
+        title: "Devil Powers".to_string(),
+        abilities: vec![Ability {
+            name: "Fox Devil Contract".to_string(),
+            description: "Summons the head of the Fox Devil".to_string(),
+            abilities: Vec::new(),
+        }],
+    }];
+    detail.revision_id = Some(42);
+    detail.revision_timestamp = Some("2022-10-11T15:00:00Z".parse().unwrap());
+    detail.warnings = vec![ParseWarning::new("gender", "unknown gender")];
+    detail
+}
+
+#[tokio::test]
+async fn stores_character_details_like_devil_details() {
+    let repository = SqliteDevilRepository::open(database_path("character_details")).unwrap();
+    let detail = aki_detail();
+    repository
+        .save_characters(&[Character::from(&detail)], std::slice::from_ref(&detail))
+        .await
+        .unwrap();
+
+    let stored = repository
+        .find_character_detail("aki-hayakawa")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&stored).unwrap(),
+        serde_json::to_value(&detail).unwrap()
+    );
+    assert_eq!(
+        repository.find_character_revisions().await.unwrap()["aki-hayakawa"].id,
+        42
+    );
+
+    let links = repository.find_character_links().await.unwrap();
+    assert_eq!(links["aki-hayakawa"].contracts, detail.contracts);
+    assert!(links["aki-hayakawa"].relatives.is_empty());
+}
+
+#[tokio::test]
+async fn migrates_character_details_stored_as_json() {
+    let path = database_path("character_details_json");
+    let detail = aki_detail();
+    {
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE characters (id TEXT PRIMARY KEY, name TEXT NOT NULL, alias_name TEXT, wiki_url TEXT NOT NULL, category TEXT NOT NULL);
+            CREATE TABLE character_details (character_id TEXT PRIMARY KEY REFERENCES characters (id) ON DELETE CASCADE, revision_id INTEGER, revision_timestamp TEXT, detail TEXT NOT NULL);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO characters (id, name, wiki_url, category) VALUES (?1, ?2, ?3, ?4)",
+            [&detail.id, &detail.name, &detail.wiki_url, &detail.category],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO character_details (character_id, revision_id, revision_timestamp, detail) VALUES (?1, 42, ?2, ?3)",
+            rusqlite::params![
+                detail.id,
+                detail.revision_timestamp,
+                serde_json::to_string(&detail).unwrap()
+            ],
+        )
+        .unwrap();
+    }
+
+    let repository = SqliteDevilRepository::open(&path).unwrap();
+
+    let stored = repository
+        .find_character_detail("aki-hayakawa")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&stored).unwrap(),
+        serde_json::to_value(&detail).unwrap()
+    );
+    assert_eq!(
+        repository.find_character_revisions().await.unwrap().len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn saves_pages_listed_in_several_galleries_once() {
+    let repository = SqliteDevilRepository::open(database_path("duplicates")).unwrap();
+
+    // Power is listed in both the Fiends and the Hybrids galleries
+    let devils = vec![
+        devil("power", "Power", DevilCategory::Fiend),
+        devil("bat-devil", "Bat Devil", DevilCategory::Normal),
+        devil("power", "Power", DevilCategory::Hybrid),
+    ];
+    let details = devils.iter().map(devil_detail).collect::<Vec<_>>();
+    repository.save(&devils, &details).await.unwrap();
+
+    let stored = repository.find_devils().await.unwrap();
+    assert_eq!(
+        stored
+            .iter()
+            .map(|devil| (devil.id.as_str(), devil.category.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("bat-devil", DevilCategory::Normal),
+            ("power", DevilCategory::Fiend),
+        ]
+    );
+    assert!(repository
+        .find_devil_detail("power")
+        .await
+        .unwrap()
+        .is_some());
+
+    let characters = vec![
+        character("aki-hayakawa", "Aki Hayakawa", "Public Safety"),
+        character("aki-hayakawa", "Aki Hayakawa", "Devil Hunters"),
+    ];
+    let details = characters.iter().map(character_detail).collect::<Vec<_>>();
+    repository
+        .save_characters(&characters, &details)
+        .await
+        .unwrap();
+
+    let stored = repository.find_characters().await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].category, "Public Safety");
+}
+
+#[tokio::test]
+async fn read_only_services_serve_the_store_without_scraping() {
+    let repository = Arc::new(SqliteDevilRepository::open(database_path("read_only")).unwrap());
+    let devils = vec![devil("bat-devil", "Bat Devil", DevilCategory::Normal)];
+    let details = devils.iter().map(devil_detail).collect::<Vec<_>>();
+    repository.save(&devils, &details).await.unwrap();
+
+    let devil_service = DevilService::read_only(repository.clone());
+    assert_eq!(devil_service.get_devils().await.unwrap().len(), 1);
+    assert!(matches!(
+        devil_service.scrape(false).await,
+        Err(ServiceError::NoDataSource)
+    ));
+
+    let character_service = CharacterService::read_only(repository);
+    assert!(character_service.get_characters().await.unwrap().is_empty());
+    assert!(matches!(
+        character_service.scrape(false).await,
+        Err(ServiceError::NoDataSource)
+    ));
+}
@@ -0,0 +1,428 @@
+use std::{
+    future::Future,
+    net::{SocketAddr, TcpListener},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    http::{
+        header::{
+            HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+            USER_AGENT,
+        },
+        HeaderMap, StatusCode,
+    },
+    response::IntoResponse,
+    routing::get,
+    Extension, Router,
+};
+use chainsawman_api::scraper::{
+    CacheMode, Fetcher, HttpFetcher, HttpFetcherConfig, ScrapeError, DEFAULT_USER_AGENT,
+};
+
+/// Serves `/page` from a local stub server, answering the n-th request (starting at 0)
+/// with the response returned by `respond`
+async fn stub_server<F, Fut, R>(respond: F) -> (SocketAddr, Arc<AtomicUsize>)
+where
+    F: Fn(usize) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = R> + Send + 'static,
+    R: IntoResponse + 'static,
+{
+    let (app, requests) = stub_router(respond);
+    (serve(app), requests)
+}
+
+fn stub_router<F, Fut, R>(respond: F) -> (Router, Arc<AtomicUsize>)
+where
+    F: Fn(usize) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = R> + Send + 'static,
+    R: IntoResponse + 'static,
+{
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    let app = Router::new()
+        .route(
+            "/page",
+            get(move |Extension(counter): Extension<Arc<AtomicUsize>>| {
+                respond(counter.fetch_add(1, Ordering::SeqCst))
+            }),
+        )
+        .layer(Extension(counter));
+
+    (app, requests)
+}
+
+fn serve(app: Router) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+    });
+
+    addr
+}
+
+fn fetcher(max_retries: u32) -> HttpFetcher {
+    HttpFetcher::new(HttpFetcherConfig {
+        timeout: Duration::from_millis(500),
+        max_retries,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_secs(2),
+        requests_per_second: 1000.0,
+        burst: 100,
+        ..HttpFetcherConfig::default()
+    })
+}
+
+fn url_of(addr: SocketAddr) -> String {
+    format!("http://{}/page", addr)
+}
+
+#[tokio::test]
+async fn retries_server_errors_until_success() {
+    let (addr, requests) = stub_server(|n| async move {
+        match n {
+            0 => (StatusCode::SERVICE_UNAVAILABLE, "unavailable"),
+            1 => (StatusCode::BAD_GATEWAY, "bad gateway"),
+            _ => (StatusCode::OK, "devil"),
+        }
+    })
+    .await;
+
+    let body = fetcher(3).fetch(&url_of(addr)).await.unwrap();
+
+    assert_eq!(body, "devil");
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let (addr, requests) =
+        stub_server(|_| async { (StatusCode::INTERNAL_SERVER_ERROR, "internal server error") })
+            .await;
+
+    let err = fetcher(2).fetch(&url_of(addr)).await.unwrap_err();
+
+    assert!(matches!(err, ScrapeError::HttpStatus { status: 500, .. }));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let (addr, requests) = stub_server(|_| async { (StatusCode::NOT_FOUND, "not found") }).await;
+
+    let err = fetcher(3).fetch(&url_of(addr)).await.unwrap_err();
+
+    assert!(matches!(err, ScrapeError::HttpStatus { status: 404, .. }));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn respects_retry_after_on_too_many_requests() {
+    let (addr, requests) = stub_server(|n| async move {
+        match n {
+            0 => (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, "1")],
+                "slow down",
+            ),
+            _ => (StatusCode::OK, [(RETRY_AFTER, "0")], "devil"),
+        }
+    })
+    .await;
+
+    let started_at = Instant::now();
+    let body = fetcher(1).fetch(&url_of(addr)).await.unwrap();
+
+    assert_eq!(body, "devil");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert!(started_at.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn honors_retry_after_longer_than_the_backoff() {
+    let (addr, requests) = stub_server(|n| async move {
+        match n {
+            0 => (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, "3")],
+                "slow down",
+            ),
+            _ => (StatusCode::OK, [(RETRY_AFTER, "0")], "devil"),
+        }
+    })
+    .await;
+
+    let started_at = Instant::now();
+    let body = fetcher(1).fetch(&url_of(addr)).await.unwrap();
+
+    assert_eq!(body, "devil");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert!(started_at.elapsed() >= Duration::from_secs(3));
+}
+
+#[tokio::test]
+async fn gives_up_when_retry_after_exceeds_the_limit() {
+    let (addr, requests) = stub_server(|_| async {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(RETRY_AFTER, "3600")],
+            "slow down",
+        )
+    })
+    .await;
+
+    let err = fetcher(3).fetch(&url_of(addr)).await.unwrap_err();
+
+    assert!(matches!(err, ScrapeError::HttpStatus { status: 429, .. }));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn times_out_slow_responses() {
+    let (addr, requests) = stub_server(|_| async {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        "devil"
+    })
+    .await;
+
+    let err = fetcher(1).fetch(&url_of(addr)).await.unwrap_err();
+
+    assert!(matches!(err, ScrapeError::Network { .. }));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn throttles_retries_with_the_rate_limiter() {
+    let (addr, requests) =
+        stub_server(|_| async { (StatusCode::SERVICE_UNAVAILABLE, "unavailable") }).await;
+    let fetcher = HttpFetcher::new(HttpFetcherConfig {
+        requests_per_second: 10.0,
+        burst: 1,
+        initial_backoff: Duration::ZERO,
+        ..HttpFetcherConfig::default()
+    });
+
+    let started_at = Instant::now();
+    let err = fetcher.fetch(&url_of(addr)).await.unwrap_err();
+
+    assert!(matches!(err, ScrapeError::HttpStatus { status: 503, .. }));
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+    assert!(started_at.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn sends_the_configured_user_agent() {
+    let app = Router::new().route(
+        "/page",
+        get(|headers: HeaderMap| async move {
+            headers
+                .get(USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        }),
+    );
+    let addr = serve(app);
+
+    let default_agent = fetcher(0).fetch(&url_of(addr)).await.unwrap();
+    let custom_agent = HttpFetcher::new(HttpFetcherConfig {
+        user_agent: "devil-hunter/1.0 (+mailto:makima@example.com)".to_string(),
+        ..HttpFetcherConfig::default()
+    })
+    .fetch(&url_of(addr))
+    .await
+    .unwrap();
+
+    assert_eq!(default_agent, DEFAULT_USER_AGENT);
+    assert_eq!(
+        custom_agent,
+        "devil-hunter/1.0 (+mailto:makima@example.com)"
+    );
+}
+
+#[tokio::test]
+async fn refuses_pages_disallowed_by_robots_txt() {
+    let (app, requests) = stub_router(|_| async { "devil" });
+    let robots_requests = Arc::new(AtomicUsize::new(0));
+    let counter = robots_requests.clone();
+    let addr = serve(app.route(
+        "/robots.txt",
+        get(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { "User-agent: *\nDisallow: /page\nAllow: /page/allowed\n" }
+        }),
+    ));
+    let fetcher = fetcher(0);
+
+    let err = fetcher.fetch(&url_of(addr)).await.unwrap_err();
+    fetcher.fetch(&url_of(addr)).await.unwrap_err();
+
+    assert!(matches!(err, ScrapeError::Disallowed { .. }));
+    assert_eq!(err.kind(), "disallowed");
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+    assert_eq!(robots_requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn honors_crawl_delay_of_robots_txt() {
+    let (app, requests) = stub_router(|_| async { "devil" });
+    let addr = serve(app.route(
+        "/robots.txt",
+        get(|| async { "User-agent: chainsawman-api\nCrawl-delay: 0.2\n" }),
+    ));
+    let fetcher = fetcher(0);
+
+    let started_at = Instant::now();
+    for _ in 0..3 {
+        fetcher.fetch(&url_of(addr)).await.unwrap();
+    }
+
+    // Every page waits for the crawl delay, including the first one after robots.txt
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert!(started_at.elapsed() >= Duration::from_millis(550));
+}
+
+/// Empty cache directory of a test, kept in the target directory to be inspected
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn cached_fetcher(dir: &Path, cache_mode: CacheMode) -> HttpFetcher {
+    HttpFetcher::new(HttpFetcherConfig {
+        requests_per_second: 1000.0,
+        burst: 100,
+        cache_dir: Some(dir.to_path_buf()),
+        cache_mode,
+        ..HttpFetcherConfig::default()
+    })
+}
+
+/// Serves a page with the given validator header, answering 304 when the request
+/// revalidates it, and counts the requests that were revalidated
+fn validating_server(
+    validator: HeaderName,
+    conditional: HeaderName,
+    value: &'static str,
+) -> (SocketAddr, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let revalidated = Arc::new(AtomicUsize::new(0));
+    let (request_counter, revalidated_counter) = (requests.clone(), revalidated.clone());
+
+    let app = Router::new().route(
+        "/page",
+        get(move |headers: HeaderMap| {
+            request_counter.fetch_add(1, Ordering::SeqCst);
+            let not_modified = headers
+                .get(&conditional)
+                .map(|header| header == value)
+                .unwrap_or(false);
+            if not_modified {
+                revalidated_counter.fetch_add(1, Ordering::SeqCst);
+            }
+            let validator = validator.clone();
+
+            async move {
+                if not_modified {
+                    (StatusCode::NOT_MODIFIED, [(validator, value)], "")
+                } else {
+                    (StatusCode::OK, [(validator, value)], "devil")
+                }
+            }
+        }),
+    );
+
+    (serve(app), requests, revalidated)
+}
+
+#[tokio::test]
+async fn revalidates_cached_responses_with_etag() {
+    let dir = cache_dir("revalidates_cached_responses_with_etag");
+    let (addr, requests, revalidated) = validating_server(ETAG, IF_NONE_MATCH, "\"v1\"");
+
+    let first = cached_fetcher(&dir, CacheMode::Revalidate)
+        .fetch(&url_of(addr))
+        .await
+        .unwrap();
+    let second = cached_fetcher(&dir, CacheMode::Revalidate)
+        .fetch(&url_of(addr))
+        .await
+        .unwrap();
+
+    assert_eq!(first, "devil");
+    assert_eq!(second, "devil");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert_eq!(revalidated.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn revalidates_cached_responses_with_last_modified() {
+    let dir = cache_dir("revalidates_cached_responses_with_last_modified");
+    let (addr, _, revalidated) = validating_server(
+        LAST_MODIFIED,
+        IF_MODIFIED_SINCE,
+        "Tue, 20 Oct 2022 07:28:00 GMT",
+    );
+
+    for _ in 0..3 {
+        let body = cached_fetcher(&dir, CacheMode::Revalidate)
+            .fetch(&url_of(addr))
+            .await
+            .unwrap();
+        assert_eq!(body, "devil");
+    }
+
+    assert_eq!(revalidated.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn refresh_ignores_cached_responses() {
+    let dir = cache_dir("refresh_ignores_cached_responses");
+    let (addr, requests, revalidated) = validating_server(ETAG, IF_NONE_MATCH, "\"v1\"");
+
+    cached_fetcher(&dir, CacheMode::Revalidate)
+        .fetch(&url_of(addr))
+        .await
+        .unwrap();
+    let body = cached_fetcher(&dir, CacheMode::Refresh)
+        .fetch(&url_of(addr))
+        .await
+        .unwrap();
+
+    assert_eq!(body, "devil");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert_eq!(revalidated.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn offline_serves_cached_responses_only() {
+    let dir = cache_dir("offline_serves_cached_responses_only");
+    let (addr, requests, _) = validating_server(ETAG, IF_NONE_MATCH, "\"v1\"");
+
+    cached_fetcher(&dir, CacheMode::Revalidate)
+        .fetch(&url_of(addr))
+        .await
+        .unwrap();
+    let offline = cached_fetcher(&dir, CacheMode::Offline);
+    let body = offline.fetch(&url_of(addr)).await.unwrap();
+    let err = offline
+        .fetch(&format!("http://{}/uncached", addr))
+        .await
+        .unwrap_err();
+
+    assert_eq!(body, "devil");
+    assert!(matches!(err, ScrapeError::NotCached { .. }));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,258 @@
+mod common;
+
+use std::sync::Arc;
+
+use chainsawman_api::{
+    database::SqliteDevilRepository,
+    models::{DevilCategory, DevilDetail, EdgeKind, Graph, GraphEdge, GraphNode, NodeKind},
+    services::{CharacterRepository, DevilRepository, GraphService},
+};
+
+use common::{character, character_detail, database_path, devil, devil_detail, link};
+
+fn node(id: &str, name: &str, kind: NodeKind) -> GraphNode {
+    GraphNode {
+        id: id.to_string(),
+        name: name.to_string(),
+        kind,
+        wiki_url: format!(
+            "https://chainsaw-man.fandom.com/wiki/{}",
+            name.replace(' ', "_")
+        ),
+    }
+}
+
+fn edge(source: &str, target: &str, kind: EdgeKind, qualifier: Option<&str>) -> GraphEdge {
+    GraphEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind,
+        qualifier: qualifier.map(|qualifier| qualifier.to_string()),
+    }
+}
+
+/// bat-devil - leech-devil are relatives, bat-devil - rio and fox-devil - aki-hayakawa are
+/// contracts, aki-hayakawa - taiyo-hayakawa are relatives and power is alone
+fn graph() -> Graph {
+    Graph {
+        nodes: vec![
+            node("aki-hayakawa", "Aki Hayakawa", NodeKind::Character),
+            node("bat-devil", "Bat Devil", NodeKind::Devil),
+            node("fox-devil", "Fox Devil", NodeKind::Devil),
+            node("leech-devil", "Leech Devil", NodeKind::Devil),
+            node("power", "Power", NodeKind::Devil),
+            node("rio", "Rio", NodeKind::Character),
+            node("taiyo-hayakawa", "Taiyo Hayakawa", NodeKind::Character),
+        ],
+        edges: vec![
+            edge(
+                "bat-devil",
+                "leech-devil",
+                EdgeKind::Relative,
+                Some("partner"),
+            ),
+            edge("bat-devil", "rio", EdgeKind::Contract, None),
+            edge("fox-devil", "aki-hayakawa", EdgeKind::Contract, None),
+            edge(
+                "aki-hayakawa",
+                "taiyo-hayakawa",
+                EdgeKind::Relative,
+                Some("brother"),
+            ),
+        ],
+    }
+}
+
+fn ids(nodes: &[GraphNode]) -> Vec<&str> {
+    nodes.iter().map(|node| node.id.as_str()).collect()
+}
+
+#[test]
+fn finds_neighbors_in_both_directions() {
+    let graph = graph();
+
+    let neighbors = graph.neighbors("leech-devil", &[]).unwrap();
+    assert_eq!(neighbors.len(), 1);
+    assert_eq!(neighbors[0].node.id, "bat-devil");
+    assert_eq!(neighbors[0].edge.qualifier.as_deref(), Some("partner"));
+
+    let neighbors = graph.neighbors("bat-devil", &[]).unwrap();
+    let neighbor_ids = neighbors
+        .iter()
+        .map(|neighbor| neighbor.node.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(neighbor_ids, vec!["leech-devil", "rio"]);
+
+    let neighbors = graph.neighbors("bat-devil", &[EdgeKind::Contract]).unwrap();
+    assert_eq!(neighbors.len(), 1);
+    assert_eq!(neighbors[0].node.id, "rio");
+
+    assert!(graph.neighbors("power", &[]).unwrap().is_empty());
+    assert!(graph.neighbors("makima", &[]).is_none());
+}
+
+#[test]
+fn finds_the_shortest_path() {
+    let mut graph = graph();
+    graph
+        .edges
+        .push(edge("rio", "fox-devil", EdgeKind::Contract, None));
+
+    let path = graph
+        .shortest_path("leech-devil", "taiyo-hayakawa", &[])
+        .unwrap();
+    assert_eq!(
+        ids(&path.nodes),
+        vec![
+            "leech-devil",
+            "bat-devil",
+            "rio",
+            "fox-devil",
+            "aki-hayakawa",
+            "taiyo-hayakawa"
+        ]
+    );
+    assert_eq!(path.edges.len(), 5);
+    assert_eq!(path.edges[0].kind, EdgeKind::Relative);
+
+    let path = graph.shortest_path("power", "power", &[]).unwrap();
+    assert_eq!(ids(&path.nodes), vec!["power"]);
+    assert!(path.edges.is_empty());
+
+    assert!(graph
+        .shortest_path("leech-devil", "taiyo-hayakawa", &[EdgeKind::Relative])
+        .is_none());
+    assert!(graph.shortest_path("bat-devil", "power", &[]).is_none());
+}
+
+#[test]
+fn splits_connected_components_by_edge_kind() {
+    let graph = graph();
+
+    let components = graph.components(&[]);
+    let members = components
+        .iter()
+        .map(|component| ids(&component.nodes))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        vec![
+            vec!["aki-hayakawa", "fox-devil", "taiyo-hayakawa"],
+            vec!["bat-devil", "leech-devil", "rio"],
+        ]
+    );
+    assert_eq!(components[1].edges.len(), 2);
+
+    let components = graph.components(&[EdgeKind::Relative]);
+    let members = components
+        .iter()
+        .map(|component| ids(&component.nodes))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        vec![
+            vec!["aki-hayakawa", "taiyo-hayakawa"],
+            vec!["bat-devil", "leech-devil"],
+        ]
+    );
+    assert!(components
+        .iter()
+        .flat_map(|component| &component.edges)
+        .all(|edge| edge.kind == EdgeKind::Relative));
+}
+
+#[test]
+fn exports_graphml() {
+    let mut graph = graph();
+    graph.nodes[4].name = "Power & \"Blood\"".to_string();
+
+    let graphml = graph.to_graphml();
+
+    assert!(graphml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert!(graphml.contains(r#"<graph id="chainsawman" edgedefault="directed">"#));
+    assert!(graphml.contains(
+        r#"<node id="bat-devil"><data key="name">Bat Devil</data><data key="kind">devil</data>"#
+    ));
+    assert!(graphml.contains(r#"<data key="name">Power &amp; &quot;Blood&quot;</data>"#));
+    assert!(graphml.contains(
+        r#"<edge source="bat-devil" target="leech-devil"><data key="edge_kind">relative</data><data key="qualifier">partner</data></edge>"#
+    ));
+    assert_eq!(graphml.matches("<node ").count(), 7);
+    assert_eq!(graphml.matches("<edge ").count(), 4);
+    assert!(graphml.ends_with("</graphml>\n"));
+}
+
+#[test]
+fn exports_dot() {
+    let mut graph = graph();
+    graph.nodes[4].name = "Power \"Blood\"".to_string();
+
+    let dot = graph.to_dot();
+
+    assert!(dot.starts_with("digraph chainsawman {\n"));
+    assert!(dot.contains(r#""power" [label="Power \"Blood\"", kind="devil""#));
+    assert!(dot.contains(
+        r#""bat-devil" -> "leech-devil" [label="relative (partner)", kind="relative"];"#
+    ));
+    assert!(dot.contains(r#""bat-devil" -> "rio" [label="contract", kind="contract"];"#));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[tokio::test]
+async fn builds_the_graph_from_stored_links() {
+    let repository = Arc::new(SqliteDevilRepository::open(database_path("graph")).unwrap());
+
+    let bat_devil = devil("bat-devil", "Bat Devil", DevilCategory::Normal);
+    let leech_devil = devil("leech-devil", "Leech Devil", DevilCategory::Normal);
+    let details = vec![
+        DevilDetail {
+            contracts: vec![
+                link("Rio", Some("Rio"), None),
+                link("Kobeni", Some("Kobeni_Higashiyama"), None),
+            ],
+            relatives: vec![link("Leech Devil", Some("Leech_Devil"), Some("partner"))],
+            ..devil_detail(&bat_devil)
+        },
+        devil_detail(&leech_devil),
+    ];
+    repository
+        .save(&[bat_devil, leech_devil], &details)
+        .await
+        .unwrap();
+
+    let rio = character("rio", "Rio", "Humans");
+    let rio_detail = character_detail(&rio);
+    repository
+        .save_characters(&[rio], &[rio_detail])
+        .await
+        .unwrap();
+
+    let service = GraphService::new(repository.clone(), repository);
+    let graph = service.get_graph(&[]).await.unwrap();
+
+    assert_eq!(ids(&graph.nodes), vec!["bat-devil", "leech-devil", "rio"]);
+    assert_eq!(graph.nodes[2].kind, NodeKind::Character);
+    // Kobeni isn't in the dataset so her link isn't an edge
+    assert_eq!(
+        graph.edges,
+        vec![
+            edge("bat-devil", "rio", EdgeKind::Contract, None),
+            edge(
+                "bat-devil",
+                "leech-devil",
+                EdgeKind::Relative,
+                Some("partner")
+            ),
+        ]
+    );
+
+    let graph = service.get_graph(&[EdgeKind::Contract]).await.unwrap();
+    assert_eq!(graph.edges.len(), 1);
+
+    let path = service
+        .get_shortest_path("leech-devil", "rio", &[])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ids(&path.nodes), vec!["leech-devil", "bat-devil", "rio"]);
+}
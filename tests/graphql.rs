@@ -0,0 +1,150 @@
+mod common;
+
+use std::sync::Arc;
+
+use chainsawman_api::{
+    database::SqliteDevilRepository,
+    graphql::{schema, RootSchema},
+    models::{Ability, AbilitySection, DevilCategory, DevilDetail},
+    services::{
+        CharacterRepository, CharacterService, DevilRepository, DevilService, GraphService,
+    },
+};
+use serde_json::{json, Value};
+
+use common::{character, character_detail, database_path, devil, devil_detail};
+
+fn ability(name: &str, description: &str, abilities: Vec<Ability>) -> Ability {
+    Ability {
+        name: name.to_string(),
+        description: description.to_string(),
+        abilities,
+    }
+}
+
+/// Schema over a repository holding power, the bat devil with its abilities and rio
+async fn graphql_schema(name: &str) -> RootSchema {
+    let repository = Arc::new(SqliteDevilRepository::open(database_path(name)).unwrap());
+
+    let power = devil("power", "Power", DevilCategory::Fiend);
+    let bat_devil = devil("bat-devil", "Bat Devil", DevilCategory::Normal);
+    let details = vec![
+        devil_detail(&power),
+        DevilDetail {
+            abilities: vec![AbilitySection {
+                key: "devil".to_string(),
+                title: "Devil Powers".to_string(),
+                abilities: vec![ability(
+                    "Flight",
+                    "The Bat Devil can fly.",
+                    vec![ability(
+                        "Sonar",
+                        "It locates its prey by sound.",
+                        Vec::new(),
+                    )],
+                )],
+            }],
+            ..devil_detail(&bat_devil)
+        },
+    ];
+    repository
+        .save(&[power, bat_devil], &details)
+        .await
+        .unwrap();
+
+    let rio = character("rio", "Rio", "Humans");
+    let rio_detail = character_detail(&rio);
+    repository
+        .save_characters(&[rio], &[rio_detail])
+        .await
+        .unwrap();
+
+    schema(
+        Arc::new(DevilService::read_only(repository.clone())),
+        Arc::new(CharacterService::read_only(repository.clone())),
+        Arc::new(GraphService::new(repository.clone(), repository)),
+    )
+}
+
+async fn execute(schema: &RootSchema, query: &str) -> Value {
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    response.data.into_json().unwrap()
+}
+
+#[tokio::test]
+async fn queries_the_devils() {
+    let schema = graphql_schema("queries_the_devils").await;
+
+    let data = execute(&schema, "{ devils { id category } }").await;
+    let mut devils = data["devils"].as_array().unwrap().clone();
+    devils.sort_by_key(|devil| devil["id"].as_str().unwrap().to_string());
+    assert_eq!(
+        devils,
+        vec![
+            json!({ "id": "bat-devil", "category": "NORMAL" }),
+            json!({ "id": "power", "category": "FIEND" }),
+        ]
+    );
+
+    let data = execute(&schema, "{ devils(category: FIEND) { id } }").await;
+    assert_eq!(data, json!({ "devils": [{ "id": "power" }] }));
+}
+
+#[tokio::test]
+async fn queries_a_devil_by_id() {
+    let schema = graphql_schema("queries_a_devil_by_id").await;
+
+    let data = execute(&schema, r#"{ devil(id: "power") { id devilName } }"#).await;
+    assert_eq!(
+        data,
+        json!({ "devil": { "id": "power", "devilName": "Power" } })
+    );
+
+    let data = execute(&schema, r#"{ devil(id: "reze") { id } }"#).await;
+    assert_eq!(data, json!({ "devil": null }));
+
+    let data = execute(&schema, r#"{ character(id: "rio") { name } }"#).await;
+    assert_eq!(data, json!({ "character": { "name": "Rio" } }));
+}
+
+#[tokio::test]
+async fn queries_the_nested_abilities_of_a_devil() {
+    let schema = graphql_schema("queries_the_nested_abilities_of_a_devil").await;
+
+    let query = r#"{
+        devil(id: "bat-devil") {
+            detail {
+                abilities {
+                    kind
+                    title
+                    abilities { name abilities { name description abilities { name } } }
+                }
+            }
+        }
+    }"#;
+    let data = execute(&schema, query).await;
+
+    assert_eq!(
+        data,
+        json!({
+            "devil": {
+                "detail": {
+                    "abilities": [{
+                        "kind": "devil",
+                        "title": "Devil Powers",
+                        "abilities": [{
+                            "name": "Flight",
+                            "abilities": [{
+                                "name": "Sonar",
+                                "description": "It locates its prey by sound.",
+                                "abilities": [],
+                            }],
+                        }],
+                    }],
+                },
+            },
+        })
+    );
+}
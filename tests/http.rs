@@ -0,0 +1,275 @@
+mod common;
+
+use std::{io::Error, sync::Arc};
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::{IntoResponse, Response},
+    Router,
+};
+use chainsawman_api::{
+    database::SqliteDevilRepository,
+    http::{router, ApiError},
+    models::{
+        Devil, DevilCategory, DevilDetail, Graph, GraphPath, Neighbor, ScrapeReport, ScrapedPage,
+    },
+    services::{
+        CharacterRepository, CharacterService, DevilRepository, DevilService, GraphService,
+    },
+};
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use tower::ServiceExt;
+
+use common::{character, character_detail, database_path, devil, devil_detail, link, WIKI_URL};
+
+/// Repository holding power, bat-devil, leech-devil and rio, bat-devil and leech-devil are
+/// relatives and bat-devil has a contract with rio
+async fn repository(name: &str) -> Arc<SqliteDevilRepository> {
+    let repository = Arc::new(SqliteDevilRepository::open(database_path(name)).unwrap());
+
+    let power = devil("power", "Power", DevilCategory::Fiend);
+    let bat_devil = devil("bat-devil", "Bat Devil", DevilCategory::Normal);
+    let leech_devil = devil("leech-devil", "Leech Devil", DevilCategory::Normal);
+    let details = vec![
+        devil_detail(&power),
+        DevilDetail {
+            contracts: vec![link("Rio", Some("Rio"), None)],
+            relatives: vec![link("Leech Devil", Some("Leech_Devil"), Some("partner"))],
+            ..devil_detail(&bat_devil)
+        },
+        devil_detail(&leech_devil),
+    ];
+    repository
+        .save(&[power, bat_devil, leech_devil], &details)
+        .await
+        .unwrap();
+
+    let rio = character("rio", "Rio", "Humans");
+    let rio_detail = character_detail(&rio);
+    repository
+        .save_characters(&[rio], &[rio_detail])
+        .await
+        .unwrap();
+
+    repository
+}
+
+async fn app(name: &str) -> Router {
+    app_over(repository(name).await)
+}
+
+fn app_over(repository: Arc<SqliteDevilRepository>) -> Router {
+    router(
+        Arc::new(DevilService::read_only(repository.clone())),
+        Arc::new(CharacterService::read_only(repository.clone())),
+        Arc::new(GraphService::new(repository.clone(), repository)),
+    )
+}
+
+async fn get(app: Router, uri: &str) -> Response {
+    let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    app.oneshot(request).await.unwrap().into_response()
+}
+
+fn content_type(response: &Response) -> &str {
+    response.headers()[header::CONTENT_TYPE].to_str().unwrap()
+}
+
+async fn text(response: Response) -> String {
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+async fn json<T: DeserializeOwned>(response: Response) -> T {
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn ids(devils: &[Devil]) -> Vec<&str> {
+    let mut ids: Vec<&str> = devils.iter().map(|devil| devil.id.as_str()).collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[tokio::test]
+async fn lists_the_devils() {
+    let response = get(app("lists_the_devils").await, "/devils").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let devils: Vec<Devil> = json(response).await;
+    assert_eq!(ids(&devils), vec!["bat-devil", "leech-devil", "power"]);
+}
+
+#[tokio::test]
+async fn lists_the_devils_of_a_category() {
+    let app = app("lists_the_devils_of_a_category").await;
+
+    let response = get(app.clone(), "/devils?category=fiend").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let devils: Vec<Devil> = json(response).await;
+    assert_eq!(ids(&devils), vec!["power"]);
+
+    let devils: Vec<Devil> = json(get(app, "/devils?category=primal").await).await;
+    assert!(devils.is_empty());
+}
+
+#[tokio::test]
+async fn gets_a_devil() {
+    let response = get(app("gets_a_devil").await, "/devils/bat-devil").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let detail: DevilDetail = json(response).await;
+    assert_eq!(detail.devil_name, "Bat Devil");
+    assert_eq!(detail.relatives.len(), 1);
+    assert_eq!(
+        detail.relatives[0].entity_id,
+        Some("leech-devil".to_string())
+    );
+}
+
+#[tokio::test]
+async fn unknown_devils_are_not_found() {
+    let response = get(app("unknown_devils_are_not_found").await, "/devils/reze").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn gets_the_graph() {
+    let response = get(app("gets_the_graph").await, "/graph").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(content_type(&response), "application/json");
+
+    let graph: Graph = json(response).await;
+    assert_eq!(graph.nodes.len(), 4);
+    assert_eq!(graph.edges.len(), 2);
+}
+
+#[tokio::test]
+async fn exports_the_graph_in_the_requested_format() {
+    let app = app("exports_the_graph_in_the_requested_format").await;
+
+    let response = get(app.clone(), "/graph?format=graphml").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(content_type(&response), "application/graphml+xml");
+    assert!(text(response).await.ends_with("</graphml>\n"));
+
+    let response = get(app.clone(), "/graph?format=dot&kinds=contract").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(content_type(&response), "text/vnd.graphviz");
+    let dot = text(response).await;
+    assert!(dot.starts_with("digraph chainsawman {\n"));
+    assert!(dot.contains(r#""bat-devil" -> "rio""#));
+    assert!(!dot.contains(r#""bat-devil" -> "leech-devil""#));
+
+    let response = get(app, "/graph?kinds=friend").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn gets_the_neighbors_of_an_entity() {
+    let app = app("gets_the_neighbors_of_an_entity").await;
+
+    let response = get(app.clone(), "/graph/neighbors/bat-devil").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let neighbors: Vec<Neighbor> = json(response).await;
+    let mut ids: Vec<&str> = neighbors
+        .iter()
+        .map(|neighbor| neighbor.node.id.as_str())
+        .collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec!["leech-devil", "rio"]);
+
+    let response = get(app, "/graph/neighbors/reze").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn gets_the_shortest_path_between_entities() {
+    let app = app("gets_the_shortest_path_between_entities").await;
+
+    let response = get(app.clone(), "/graph/path/leech-devil/rio").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let path: GraphPath = json(response).await;
+    let ids: Vec<&str> = path.nodes.iter().map(|node| node.id.as_str()).collect();
+    assert_eq!(ids, vec!["leech-devil", "bat-devil", "rio"]);
+
+    // power isn't linked to anyone
+    let response = get(app.clone(), "/graph/path/power/rio").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = get(app, "/graph/path/reze/rio").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn gets_the_components_of_the_graph() {
+    let response = get(
+        app("gets_the_components_of_the_graph").await,
+        "/graph/components",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // power isn't linked to anyone so it isn't in any component
+    let components: Vec<Graph> = json(response).await;
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].nodes.len(), 3);
+}
+
+#[tokio::test]
+async fn gets_the_latest_report_of_an_entity() {
+    let repository = repository("gets_the_latest_report_of_an_entity").await;
+    let report = |id: &str| ScrapeReport {
+        started_at: Utc::now(),
+        finished_at: Utc::now(),
+        succeeded: vec![ScrapedPage {
+            id: id.to_string(),
+            wiki_url: format!("{}/{}", WIKI_URL, id),
+            category: "Devils".to_string(),
+            duration_ms: 10,
+            warnings: Vec::new(),
+        }],
+        failed: Vec::new(),
+        unchanged: Vec::new(),
+    };
+    let app = app_over(repository.clone());
+
+    let response = get(app.clone(), "/scrape-report?entity=characters").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    repository.save_report(&report("power")).await.unwrap();
+    repository
+        .save_character_report(&report("rio"))
+        .await
+        .unwrap();
+
+    let response = get(app.clone(), "/scrape-report").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let latest: ScrapeReport = json(response).await;
+    assert_eq!(latest.succeeded[0].id, "power");
+
+    let latest: ScrapeReport = json(get(app, "/scrape-report?entity=characters").await).await;
+    assert_eq!(latest.succeeded[0].id, "rio");
+}
+
+#[tokio::test]
+async fn store_errors_are_internal_server_errors() {
+    let error = ApiError::from(Error::other("disk I/O error"));
+
+    assert_eq!(
+        error.to_string(),
+        "Unable to read the stored data: disk I/O error"
+    );
+
+    let response = error.into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    // The text of the store error is only logged
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+        serde_json::json!({ "message": "Internal server error" })
+    );
+}
@@ -0,0 +1,50 @@
+use std::{sync::Arc, time::Duration};
+
+use chainsawman_api::scraper::RateLimiter;
+use tokio::time::Instant;
+
+#[tokio::test]
+async fn lets_a_burst_through_at_once() {
+    let limiter = RateLimiter::new(1.0, 3);
+
+    let started_at = Instant::now();
+    for _ in 0..3 {
+        limiter.acquire().await;
+    }
+
+    assert!(started_at.elapsed() < Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn throttles_requests_after_the_burst() {
+    let limiter = RateLimiter::new(20.0, 2);
+
+    let started_at = Instant::now();
+    for _ in 0..6 {
+        limiter.acquire().await;
+    }
+
+    // 2 requests from the burst, then 4 requests at 50ms each
+    let elapsed = started_at.elapsed();
+    assert!(elapsed >= Duration::from_millis(190), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(1), "{:?}", elapsed);
+}
+
+#[tokio::test]
+async fn is_shared_by_concurrent_workers() {
+    let limiter = Arc::new(RateLimiter::new(20.0, 1));
+
+    let started_at = Instant::now();
+    let handles = (0..5)
+        .map(|_| {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire().await })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    // 1 request from the burst, then 4 requests at 50ms each
+    assert!(started_at.elapsed() >= Duration::from_millis(190));
+}
@@ -0,0 +1,779 @@
+mod common;
+
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use chainsawman_api::{
+    database::SqliteDevilRepository,
+    models::{Dataset, DevilCategory, DevilDetail, Gender, ParseWarning, Status},
+    scraper::{
+        DevilScraperConfig, Fetcher, MediaWikiScraper, ScrapeError, ScraperSelectors,
+        DEFAULT_SELECTORS,
+    },
+    services::{CharacterDataSource, CharacterService, DevilDataSource, DevilService},
+};
+
+use common::{database_path, find_devil, link};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/mediawiki");
+
+const REVISIONS_URL: &str = "https://chainsaw-man.fandom.com/api.php?action=query&prop=revisions&rvprop=ids%7Ctimestamp&titles=Bat%20Devil%7CDarkness%20Devil%7CDenji%7CLeech%20Devil%7CNayuta%7CPower&redirects=1&format=json&formatversion=2";
+
+/// Serves API responses recorded in the fixtures, `index.json` maps every url to its file
+struct RecordedFetcher {
+    root: PathBuf,
+    index: HashMap<String, String>,
+    fetched: Mutex<Vec<String>>,
+}
+
+impl RecordedFetcher {
+    fn new(root: &str) -> Self {
+        let root = PathBuf::from(root);
+        let index = std::fs::read_to_string(root.join("index.json")).unwrap();
+
+        RecordedFetcher {
+            index: serde_json::from_str(&index).unwrap(),
+            root,
+            fetched: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Serves the given file instead of the recorded response of the url
+    fn with_response(mut self, url: &str, file: &str) -> Self {
+        self.index.insert(url.to_string(), file.to_string());
+        self
+    }
+
+    fn fetched(&self) -> Vec<String> {
+        self.fetched.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Fetcher for RecordedFetcher {
+    async fn fetch(&self, url: &str) -> Result<String, ScrapeError> {
+        self.fetched.lock().unwrap().push(url.to_string());
+
+        let io_error = |error: Error| ScrapeError::Io {
+            url: url.to_string(),
+            error,
+        };
+
+        let file = self
+            .index
+            .get(url)
+            .ok_or_else(|| io_error(Error::new(ErrorKind::NotFound, "no recorded response")))?;
+
+        tokio::fs::read_to_string(self.root.join(file))
+            .await
+            .map_err(io_error)
+    }
+}
+
+fn scraper() -> MediaWikiScraper {
+    MediaWikiScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        Arc::new(RecordedFetcher::new(FIXTURES_DIR)),
+    )
+}
+
+fn service(fetcher: Arc<RecordedFetcher>, database: &Path) -> DevilService {
+    DevilService::new(
+        Arc::new(MediaWikiScraper::new(
+            DevilScraperConfig {
+                num_of_workers: 2,
+                ..DevilScraperConfig::default()
+            },
+            fetcher,
+        )),
+        Arc::new(SqliteDevilRepository::open(database).unwrap()),
+    )
+}
+
+fn parse_url(title: &str) -> String {
+    format!(
+        "https://chainsaw-man.fandom.com/api.php?action=parse&page={}&prop=wikitext&redirects=1&format=json&formatversion=2",
+        title.replace(' ', "%20")
+    )
+}
+
+#[tokio::test]
+async fn scrapes_devils_from_category_galleries() {
+    let mut devils = scraper().scrape_devils().await.unwrap();
+    devils.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let summary = devils
+        .iter()
+        .map(|devil| {
+            (
+                devil.id.as_str(),
+                devil.devil_name.as_str(),
+                devil.alias_name.as_deref(),
+                devil.category.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("bat-devil", "Bat Devil", None, "normal"),
+            ("darkness-devil", "Darkness Devil", None, "primal"),
+            ("denji", "Chainsaw Man", Some("Denji"), "hybrid"),
+            ("leech-devil", "Leech Devil", None, "normal"),
+            ("nayuta", "Control Devil", Some("Nayuta"), "reincarnated"),
+            ("power", "Power", None, "fiend"),
+        ]
+    );
+    assert_eq!(
+        devils[0].wiki_url,
+        "https://chainsaw-man.fandom.com/wiki/Bat_Devil"
+    );
+}
+
+/// Scraper reading the galleries of the given selector mapping
+fn scraper_with_selectors(selectors: &str) -> MediaWikiScraper {
+    MediaWikiScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            selectors: Arc::new(ScraperSelectors::from_toml(selectors).unwrap()),
+        },
+        Arc::new(RecordedFetcher::new(FIXTURES_DIR)),
+    )
+}
+
+#[tokio::test]
+async fn reads_the_galleries_of_the_selectors() {
+    // Swapping the ids of two galleries swaps the categories of their devils
+    let selectors = DEFAULT_SELECTORS
+        .replace(
+            "category = \"Fiends\"\nid = \"gallery-4\"",
+            "category = \"Fiends\"\nid = \"gallery-5\"",
+        )
+        .replace(
+            "category = \"Hybrids\"\nid = \"gallery-5\"",
+            "category = \"Hybrids\"\nid = \"gallery-4\"",
+        );
+    let devils = scraper_with_selectors(&selectors)
+        .scrape_devils()
+        .await
+        .unwrap();
+
+    let category_of = |id: &str| {
+        devils
+            .iter()
+            .find(|devil| devil.id == id)
+            .map(|devil| devil.category.clone())
+    };
+    assert_eq!(category_of("power"), Some(DevilCategory::Hybrid));
+    assert_eq!(category_of("denji"), Some(DevilCategory::Fiend));
+
+    let selectors = DEFAULT_SELECTORS.replace("id = \"gallery-5\"", "id = \"gallery-9\"");
+    match scraper_with_selectors(&selectors).scrape_devils().await {
+        Err(ScrapeError::MissingSection { section, .. }) => assert_eq!(section, "gallery-9"),
+        other => panic!(
+            "expected a missing gallery, got {:?}",
+            other.map(|devils| devils.len())
+        ),
+    }
+}
+
+#[tokio::test]
+async fn scrapes_devil_detail_infobox() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "bat-devil").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(detail.id, "bat-devil");
+    assert_eq!(detail.category, DevilCategory::Normal);
+    assert_eq!(detail.names["kanji"].devil_name, "コウモリの悪魔");
+    assert_eq!(detail.names["romaji"].devil_name, "Kōmori no Akuma");
+    assert_eq!(detail.names["romaji"].alias_name, None);
+    assert_eq!(detail.gender, Some(Gender::Male));
+    assert_eq!(detail.birthplace.as_deref(), Some("Hell"));
+    assert_eq!(detail.status, Some(Status::Deceased));
+    assert_eq!(
+        detail.occupations,
+        vec!["Hunting humans", "Terrorizing the city"]
+    );
+    assert_eq!(detail.affiliations, vec!["Devils", "None"]);
+    assert_eq!(detail.contracts, vec![link("Rio", Some("Rio"), None)]);
+    assert_eq!(
+        detail.relatives,
+        vec![
+            link("Leech Devil", Some("Leech_Devil"), Some("partner")),
+            link("Bat Fiend", None, None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn scrapes_kanji_names_without_their_furigana() {
+    let scraper = scraper();
+
+    let devil = find_devil(&scraper, "bat-devil").await;
+    let kanji = &scraper.scrape_devil_detail(&devil).await.unwrap().names["kanji"];
+    assert_eq!(kanji.devil_name, "コウモリの悪魔");
+    assert_eq!(kanji.reading.as_deref(), Some("コウモリのあくま"));
+    assert_eq!(kanji.alias_name, None);
+    assert_eq!(kanji.alias_reading, None);
+
+    let devil = find_devil(&scraper, "nayuta").await;
+    let kanji = &scraper.scrape_devil_detail(&devil).await.unwrap().names["kanji"];
+    assert_eq!(kanji.devil_name, "ナユタ");
+    assert_eq!(kanji.reading, None);
+    assert_eq!(kanji.alias_name.as_deref(), Some("支配の悪魔"));
+    assert_eq!(kanji.alias_reading.as_deref(), Some("しはいのあくま"));
+
+    let devil = find_devil(&scraper, "darkness-devil").await;
+    let kanji = &scraper.scrape_devil_detail(&devil).await.unwrap().names["kanji"];
+    assert_eq!(kanji.devil_name, "闇の悪魔");
+    assert_eq!(kanji.reading, None);
+    assert_eq!(kanji.alias_name, None);
+}
+
+#[tokio::test]
+async fn resolves_infobox_image_urls() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "bat-devil").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let src = "https://static.wikia.nocookie.net/chainsaw-man/images/Bat_Devil.png/revision/latest";
+    assert_eq!(detail.image_src.as_deref(), Some(src));
+    assert_eq!(detail.images.len(), 1);
+    assert_eq!(detail.images[0].src, src);
+    assert_eq!(detail.images[0].label, None);
+    assert_eq!(
+        detail.images[0].caption.as_deref(),
+        Some("The Bat Devil in its true form")
+    );
+}
+
+#[tokio::test]
+async fn resolves_normalized_image_titles() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "power").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(
+        detail.image_src.as_deref(),
+        Some("https://static.wikia.nocookie.net/chainsaw-man/images/Power.png/revision/latest")
+    );
+    assert_eq!(detail.occupations, vec!["Public Safety Devil Hunter"]);
+}
+
+#[tokio::test]
+async fn scrapes_tabbed_infobox_images_and_alias() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "denji").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let images = detail
+        .images
+        .iter()
+        .map(|image| (image.label.as_deref(), image.src.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        images,
+        vec![
+            (
+                Some("Manga"),
+                "https://static.wikia.nocookie.net/chainsaw-man/images/Denji_Manga.png/revision/latest"
+            ),
+            (
+                Some("Anime"),
+                "https://static.wikia.nocookie.net/chainsaw-man/images/Denji_Anime.png/revision/latest"
+            ),
+        ]
+    );
+    assert_eq!(detail.names["romaji"].devil_name, "Denji");
+    assert_eq!(
+        detail.names["romaji"].alias_name.as_deref(),
+        Some("Chensō Man")
+    );
+}
+
+#[tokio::test]
+async fn scrapes_every_heading_of_the_abilities_section() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "denji").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let sections = detail
+        .abilities
+        .iter()
+        .map(|section| (section.key.as_str(), section.title.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        vec![
+            ("transformation", "Hybrid Transformation"),
+            ("chainsaw_techniques", "Chainsaw Techniques"),
+            ("physical", "Physical Abilities"),
+        ]
+    );
+    assert_eq!(
+        detail.abilities_of("transformation").unwrap()[0].description,
+        "Denji grows chainsaws from his arms."
+    );
+}
+
+#[tokio::test]
+async fn scrapes_nested_abilities() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "bat-devil").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let physical = detail.abilities_of("physical").unwrap();
+    assert_eq!(physical.len(), 2);
+    assert_eq!(physical[0].name, "Enhanced Strength");
+    assert_eq!(
+        physical[0].description,
+        "The Bat Devil can easily crush buildings."
+    );
+    assert_eq!(physical[1].name, "Flight");
+
+    let devil_powers = detail.abilities_of("devil").unwrap();
+    let names = devil_powers
+        .iter()
+        .map(|ability| ability.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Regeneration", "Sonic Scream"]);
+
+    let children = devil_powers[0]
+        .abilities
+        .iter()
+        .map(|ability| ability.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(children, vec!["Blood Absorption", "Limb Regrowth"]);
+
+    let darkness = find_devil(&scraper, "darkness-devil").await;
+    let detail = scraper.scrape_devil_detail(&darkness).await.unwrap();
+    assert_eq!(
+        detail.abilities_of("supernatural").unwrap()[0].name,
+        "Darkness Manipulation"
+    );
+}
+
+#[tokio::test]
+async fn warns_about_unparseable_infobox_fields() {
+    let devil = find_devil(&scraper(), "power").await;
+    let scraper = MediaWikiScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        Arc::new(
+            RecordedFetcher::new(FIXTURES_DIR)
+                .with_response(&parse_url("Power"), "Power_unparseable.json"),
+        ),
+    );
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(detail.names["romaji"].devil_name, "Pawā");
+    assert_eq!(detail.status, None);
+    assert_eq!(
+        detail
+            .warnings
+            .iter()
+            .map(|warning| warning.field.as_str())
+            .collect::<Vec<&str>>(),
+        vec![
+            "romaji",
+            "birthplace",
+            "status",
+            "affiliation",
+            "contracted humans",
+            "relatives"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn warns_about_missing_infobox_fields() {
+    let devil = find_devil(&scraper(), "power").await;
+    let missing_gender = MediaWikiScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        Arc::new(
+            RecordedFetcher::new(FIXTURES_DIR)
+                .with_response(&parse_url("Power"), "Power_missing_gender.json"),
+        ),
+    );
+
+    let detail = missing_gender.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(detail.gender, None);
+    assert!(detail
+        .warnings
+        .contains(&ParseWarning::new("gender", "the field is missing")));
+
+    let detail = scraper().scrape_devil_detail(&devil).await.unwrap();
+    assert!(!detail
+        .warnings
+        .iter()
+        .any(|warning| warning.field == "gender"));
+}
+
+#[tokio::test]
+async fn warns_about_image_lookups_that_fail() {
+    let devil = find_devil(&scraper(), "bat-devil").await;
+    let scraper = MediaWikiScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        Arc::new(RecordedFetcher::new(FIXTURES_DIR).with_response(
+            "https://chainsaw-man.fandom.com/api.php?action=query&prop=imageinfo&iiprop=url&titles=File%3ABat%20Devil.png&format=json&formatversion=2",
+            "missing_images.json",
+        )),
+    );
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert!(detail.images.is_empty());
+    assert_eq!(detail.image_src, None);
+    assert_eq!(detail.status, Some(Status::Deceased));
+    assert_eq!(
+        detail
+            .warnings
+            .iter()
+            .map(|warning| warning.field.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["image"]
+    );
+}
+
+#[tokio::test]
+async fn reports_api_errors_without_dropping_the_scrape() {
+    let scraper = scraper();
+    let devils = scraper.scrape_devils().await.unwrap();
+
+    let report = scraper.scrape(&devils).await.unwrap().report;
+
+    assert_eq!(report.succeeded.len(), 5);
+    assert_eq!(report.failed.len(), 1);
+
+    let failed = &report.failed[0];
+    assert_eq!(failed.id, "leech-devil");
+    assert_eq!(failed.error_kind, "api");
+    assert!(failed.error.contains("missingtitle"));
+}
+
+#[tokio::test]
+async fn scrapes_page_revisions() {
+    let scraper = scraper();
+    let devils = scraper.scrape_devils().await.unwrap();
+
+    let revisions = scraper.scrape_revisions(&devils).await.unwrap();
+
+    assert_eq!(revisions.len(), 5);
+    assert_eq!(revisions["bat-devil"].id, 4120);
+    assert_eq!(
+        revisions["bat-devil"].timestamp.to_rfc3339(),
+        "2022-10-12T08:30:00+00:00"
+    );
+    assert!(!revisions.contains_key("leech-devil"));
+}
+
+#[tokio::test]
+async fn rescrapes_only_changed_pages() {
+    let database = database_path("rescrapes_only_changed_pages");
+    service(Arc::new(RecordedFetcher::new(FIXTURES_DIR)), &database)
+        .scrape(false)
+        .await
+        .unwrap();
+
+    let fetcher = Arc::new(
+        RecordedFetcher::new(FIXTURES_DIR).with_response(REVISIONS_URL, "revisions_changed.json"),
+    );
+    let service = service(fetcher.clone(), &database);
+    let result = service.scrape(false).await.unwrap();
+
+    let succeeded = result
+        .report
+        .succeeded
+        .iter()
+        .map(|devil| devil.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(succeeded, vec!["bat-devil"]);
+    assert_eq!(result.report.failed.len(), 1);
+
+    let mut unchanged = result.report.unchanged.clone();
+    unchanged.sort();
+    assert_eq!(
+        unchanged,
+        vec!["darkness-devil", "denji", "nayuta", "power"]
+    );
+    assert_eq!(result.details.len(), 5);
+
+    let fetched = fetcher.fetched();
+    assert!(fetched.contains(&parse_url("Bat Devil")));
+    assert!(!fetched.contains(&parse_url("Power")));
+
+    let detail = service.get_devil_by_id("bat-devil").await.unwrap().unwrap();
+    assert_eq!(detail.revision_id, Some(4213));
+    let detail = service.get_devil_by_id("power").await.unwrap().unwrap();
+    assert_eq!(detail.revision_id, Some(4101));
+    assert_eq!(detail.names["kanji"].devil_name, "パワー");
+}
+
+#[tokio::test]
+async fn full_scrape_rescrapes_every_page() {
+    let database = database_path("full_scrape_rescrapes_every_page");
+    service(Arc::new(RecordedFetcher::new(FIXTURES_DIR)), &database)
+        .scrape(false)
+        .await
+        .unwrap();
+
+    let fetcher = Arc::new(RecordedFetcher::new(FIXTURES_DIR));
+    let result = service(fetcher.clone(), &database)
+        .scrape(true)
+        .await
+        .unwrap();
+
+    assert_eq!(result.report.succeeded.len(), 5);
+    assert!(result.report.unchanged.is_empty());
+    assert!(fetcher.fetched().contains(&parse_url("Power")));
+    // The revisions are only used to skip unchanged pages
+    assert!(!fetcher.fetched().contains(&REVISIONS_URL.to_string()));
+}
+
+#[tokio::test]
+async fn scrapes_every_page_when_the_revisions_are_unavailable() {
+    let database = database_path("scrapes_every_page_when_the_revisions_are_unavailable");
+    service(Arc::new(RecordedFetcher::new(FIXTURES_DIR)), &database)
+        .scrape(false)
+        .await
+        .unwrap();
+
+    let fetcher = Arc::new(
+        RecordedFetcher::new(FIXTURES_DIR).with_response(REVISIONS_URL, "missing_revisions.json"),
+    );
+    let result = service(fetcher.clone(), &database)
+        .scrape(false)
+        .await
+        .unwrap();
+
+    assert!(fetcher.fetched().contains(&REVISIONS_URL.to_string()));
+    assert_eq!(result.report.succeeded.len(), 5);
+    assert!(result.report.unchanged.is_empty());
+    assert_eq!(result.details.len(), 5);
+}
+
+#[tokio::test]
+async fn scrapes_characters_with_the_devil_infobox_parsing() {
+    let scraper = scraper();
+    let characters = scraper.scrape_characters().await.unwrap();
+
+    let summary = characters
+        .iter()
+        .map(|character| (character.id.as_str(), character.category.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("aki-hayakawa", "Public Safety"),
+            ("kishibe", "Devil Hunters"),
+            ("rio", "Humans"),
+        ]
+    );
+
+    let detail = scraper
+        .scrape_character_detail(&characters[0])
+        .await
+        .unwrap();
+
+    assert_eq!(detail.name, "Aki Hayakawa");
+    assert_eq!(detail.names["romaji"].devil_name, "Hayakawa Aki");
+    assert_eq!(detail.occupations, vec!["Devil Hunter"]);
+    assert_eq!(detail.affiliations, vec!["Public Safety"]);
+    assert_eq!(
+        detail.image_src.as_deref(),
+        Some("https://static.wikia.nocookie.net/chainsaw-man/images/Aki_Hayakawa.png/revision/latest")
+    );
+    assert_eq!(
+        detail.abilities_of("supernatural").unwrap()[1].name,
+        "Curse Devil Contract"
+    );
+}
+
+#[tokio::test]
+async fn scrapes_qualified_links_to_other_pages() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "nayuta").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(
+        detail.relatives,
+        vec![link("Makima", Some("Makima"), Some("previous incarnation"))]
+    );
+}
+
+#[tokio::test]
+async fn resolves_links_to_stored_devils_and_characters() {
+    let database = database_path("resolves_links_to_stored_devils_and_characters");
+    let fetcher = Arc::new(RecordedFetcher::new(FIXTURES_DIR));
+    let repository = Arc::new(SqliteDevilRepository::open(&database).unwrap());
+    let scraper = Arc::new(MediaWikiScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        fetcher,
+    ));
+
+    let service = DevilService::new(scraper.clone(), repository.clone());
+    service.scrape(false).await.unwrap();
+    CharacterService::new(scraper, repository)
+        .scrape(false)
+        .await
+        .unwrap();
+
+    let detail = service.get_devil_by_id("bat-devil").await.unwrap().unwrap();
+
+    let contract = &detail.contracts[0];
+    assert_eq!(contract.name, "Rio");
+    assert_eq!(contract.entity_id.as_deref(), Some("rio"));
+
+    let relatives = detail
+        .relatives
+        .iter()
+        .map(|relative| {
+            (
+                relative.name.as_str(),
+                relative.entity_id.as_deref(),
+                relative.qualifier.as_deref(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        relatives,
+        vec![
+            ("Leech Devil", Some("leech-devil"), Some("partner")),
+            ("Bat Fiend", None, None),
+        ]
+    );
+
+    // Makima has no page in the dataset
+    let detail = service.get_devil_by_id("nayuta").await.unwrap().unwrap();
+    assert_eq!(detail.relatives[0].entity_id, None);
+}
+
+#[tokio::test]
+async fn keeps_the_reports_of_devils_and_characters_apart() {
+    let database = database_path("keeps_the_reports_of_devils_and_characters_apart");
+    let fetcher = Arc::new(RecordedFetcher::new(FIXTURES_DIR));
+    let repository = Arc::new(SqliteDevilRepository::open(&database).unwrap());
+    let scraper = Arc::new(MediaWikiScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        fetcher,
+    ));
+
+    let devil_service = DevilService::new(scraper.clone(), repository.clone());
+    let character_service = CharacterService::new(scraper, repository);
+    let devils = devil_service.scrape(false).await.unwrap();
+    let characters = character_service.scrape(false).await.unwrap();
+
+    let report = devil_service.get_latest_report().await.unwrap().unwrap();
+    assert_eq!(report.started_at, devils.report.started_at);
+    assert_eq!(report.succeeded.len(), devils.report.succeeded.len());
+
+    let report = character_service
+        .get_latest_report()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(report.started_at, characters.report.started_at);
+    assert_eq!(report.succeeded.len(), characters.report.succeeded.len());
+    assert_eq!(report.succeeded[0].id, "aki-hayakawa");
+}
+
+#[tokio::test]
+async fn loads_a_dataset_written_by_the_scraper() {
+    let fetcher = Arc::new(RecordedFetcher::new(FIXTURES_DIR));
+    let scraped = service(
+        fetcher.clone(),
+        &database_path("loads_a_dataset_written_by_the_scraper_scraped"),
+    )
+    .scrape(false)
+    .await
+    .unwrap();
+
+    let dataset = Dataset::new(
+        scraped.report.started_at,
+        "https://chainsaw-man.fandom.com/api.php",
+        scraped.details,
+    );
+    let json = serde_json::to_string(&dataset).unwrap();
+    let dataset: Dataset<DevilDetail> = Dataset::from_json(&json).unwrap();
+
+    let service = service(
+        fetcher,
+        &database_path("loads_a_dataset_written_by_the_scraper_loaded"),
+    );
+    service.load(&dataset).await.unwrap();
+
+    let mut ids = service
+        .get_devils()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|devil| devil.id)
+        .collect::<Vec<_>>();
+    ids.sort();
+    let mut expected = dataset
+        .entries
+        .iter()
+        .map(|detail| detail.id.clone())
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    let detail = service.get_devil_by_id("bat-devil").await.unwrap().unwrap();
+    assert_eq!(detail.category, DevilCategory::Normal);
+    assert_eq!(detail.revision_id, Some(4120));
+
+    let detail = service.get_devil_by_id("nayuta").await.unwrap().unwrap();
+    assert_eq!(
+        detail.names["kanji"].alias_reading.as_deref(),
+        Some("しはいのあくま")
+    );
+
+    // The ability sections keep the order and titles of the page once stored
+    let detail = service.get_devil_by_id("denji").await.unwrap().unwrap();
+    let titles = detail
+        .abilities
+        .iter()
+        .map(|section| section.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        vec![
+            "Hybrid Transformation",
+            "Chainsaw Techniques",
+            "Physical Abilities"
+        ]
+    );
+}
@@ -0,0 +1,272 @@
+use chainsawman_api::models::{
+    AbilitySection, CharacterDetail, Dataset, DatasetError, DevilCategory, DevilDetail, EntityLink,
+    Gender, LinkTargets, Status, DATASET_SCHEMA_VERSION, OLDEST_DATASET_SCHEMA_VERSION,
+};
+
+#[test]
+fn normalizes_devil_categories() {
+    assert_eq!(DevilCategory::parse("Normal Devils"), DevilCategory::Normal);
+    assert_eq!(DevilCategory::parse("Primal Devils"), DevilCategory::Primal);
+    assert_eq!(DevilCategory::parse("primal"), DevilCategory::Primal);
+    assert_eq!(
+        DevilCategory::parse("Reincarnated Devils"),
+        DevilCategory::Reincarnated
+    );
+    assert_eq!(DevilCategory::parse("Fiends"), DevilCategory::Fiend);
+    assert_eq!(DevilCategory::parse(" Hybrids "), DevilCategory::Hybrid);
+    assert_eq!(
+        DevilCategory::parse("Sword Devils"),
+        DevilCategory::Other("Sword Devils".to_string())
+    );
+}
+
+#[test]
+fn normalizes_infobox_statuses() {
+    assert_eq!(Status::parse("Alive"), Status::Alive);
+    assert_eq!(Status::parse("Deceased"), Status::Deceased);
+    assert_eq!(Status::parse("Dead"), Status::Deceased);
+    assert_eq!(Status::parse("Deceased (reincarnated)"), Status::Deceased);
+    assert_eq!(Status::parse("Deceased[1]"), Status::Deceased);
+    assert_eq!(Status::parse("unknown"), Status::Unknown);
+    assert_eq!(
+        Status::parse("Imprisoned "),
+        Status::Other("Imprisoned".to_string())
+    );
+}
+
+#[test]
+fn normalizes_infobox_genders() {
+    assert_eq!(Gender::parse("Male"), Gender::Male);
+    assert_eq!(Gender::parse("female"), Gender::Female);
+    assert_eq!(Gender::parse("None"), Gender::Other("None".to_string()));
+}
+
+#[test]
+fn serializes_kinds_as_their_name() {
+    assert_eq!(
+        serde_json::to_string(&DevilCategory::Primal).unwrap(),
+        r#""primal""#
+    );
+    assert_eq!(
+        serde_json::to_string(&Status::Other("Imprisoned".to_string())).unwrap(),
+        r#""Imprisoned""#
+    );
+
+    // Datasets written before the categories were normalized keep the gallery title
+    let category: DevilCategory = serde_json::from_str(r#""Primal Devils""#).unwrap();
+    assert_eq!(category, DevilCategory::Primal);
+    let status: Status = serde_json::from_str(r#""deceased""#).unwrap();
+    assert_eq!(status, Status::Deceased);
+    assert_eq!(status.other(), None);
+}
+
+#[test]
+fn round_trips_datasets() {
+    let json = format!(
+        r#"{{
+            "schema_version": {},
+            "scraped_at": "2022-11-20T10:00:00Z",
+            "source_url": "https://chainsaw-man.fandom.com",
+            "entries": [{{
+                "id": "bat-devil",
+                "devil_name": "Bat Devil",
+                "alias_name": null,
+                "wiki_url": "https://chainsaw-man.fandom.com/wiki/Bat_Devil",
+                "category": "Normal Devils",
+                "names": {{}},
+                "image_src": null,
+                "images": [],
+                "gender": "Male",
+                "birthplace": null,
+                "status": "Deceased",
+                "occupations": [],
+                "affiliations": [],
+                "contracts": [],
+                "relatives": [],
+                "abilities": [{{
+                    "key": "devil",
+                    "title": "Devil Powers",
+                    "abilities": [{{
+                        "name": "Sonic Scream",
+                        "description": "Shatters windows.",
+                        "abilities": []
+                    }}]
+                }}],
+                "revision_id": 1001,
+                "revision_timestamp": "2022-11-01T12:00:00Z"
+            }}]
+        }}"#,
+        DATASET_SCHEMA_VERSION
+    );
+
+    let dataset: Dataset<DevilDetail> = Dataset::from_json(&json).unwrap();
+    assert_eq!(dataset.source_url, "https://chainsaw-man.fandom.com");
+    assert_eq!(dataset.entries[0].category, DevilCategory::Normal);
+    assert_eq!(dataset.entries[0].status, Some(Status::Deceased));
+    assert_eq!(
+        dataset.entries[0].abilities_of("devil").unwrap()[0].name,
+        "Sonic Scream"
+    );
+
+    let json = serde_json::to_string(&dataset).unwrap();
+    let dataset: Dataset<DevilDetail> = Dataset::from_json(&json).unwrap();
+    assert_eq!(dataset.schema_version, DATASET_SCHEMA_VERSION);
+    assert_eq!(dataset.entries[0].id, "bat-devil");
+    assert_eq!(dataset.entries[0].revision_id, Some(1001));
+}
+
+#[test]
+fn migrates_version_1_datasets() {
+    let json = r#"{
+        "schema_version": 1,
+        "scraped_at": "2022-11-20T10:00:00Z",
+        "source_url": "https://chainsaw-man.fandom.com",
+        "entries": [{
+            "id": "bat-devil",
+            "devil_name": "Bat Devil",
+            "alias_name": null,
+            "wiki_url": "https://chainsaw-man.fandom.com/wiki/Bat_Devil",
+            "category": "Normal Devils",
+            "names": {},
+            "image_src": null,
+            "images": [],
+            "gender": "Male",
+            "birthplace": null,
+            "status": "Deceased",
+            "occupations": [],
+            "affiliations": [],
+            "contracts": [],
+            "relatives": [],
+            "abilities": {
+                "devil": [{"name": "Sonic Scream", "description": "", "abilities": []}]
+            },
+            "revision_id": 1001,
+            "revision_timestamp": "2022-11-01T12:00:00Z"
+        }]
+    }"#;
+
+    let dataset: Dataset<DevilDetail> = Dataset::from_json(json).unwrap();
+    assert_eq!(dataset.schema_version, DATASET_SCHEMA_VERSION);
+    assert_eq!(dataset.entries[0].abilities[0].title, "devil");
+    assert_eq!(
+        dataset.entries[0].abilities_of("devil").unwrap()[0].name,
+        "Sonic Scream"
+    );
+}
+
+#[test]
+fn rejects_datasets_of_other_versions() {
+    let unversioned = Dataset::<DevilDetail>::from_json(r#"[{"id": "bat-devil"}]"#);
+    assert!(matches!(unversioned, Err(DatasetError::Unversioned)));
+
+    let json = format!(
+        r#"{{"schema_version": {}, "entries": []}}"#,
+        DATASET_SCHEMA_VERSION + 1
+    );
+    let newer = Dataset::<DevilDetail>::from_json(&json).unwrap_err();
+    assert!(
+        matches!(newer, DatasetError::UnsupportedVersion(version) if version == DATASET_SCHEMA_VERSION + 1)
+    );
+    assert!(newer.to_string().contains("newer scraper"));
+
+    let json = format!(
+        r#"{{"schema_version": {}, "entries": []}}"#,
+        OLDEST_DATASET_SCHEMA_VERSION - 1
+    );
+    let older = Dataset::<DevilDetail>::from_json(&json).unwrap_err();
+    assert!(older.to_string().contains("older scraper"));
+
+    let json = format!(r#"{{"schema_version": {}}}"#, DATASET_SCHEMA_VERSION);
+    let invalid = Dataset::<DevilDetail>::from_json(&json).unwrap_err();
+    assert!(matches!(invalid, DatasetError::Json(_)));
+}
+
+#[test]
+fn maps_ability_headings_to_canonical_keys() {
+    assert_eq!(AbilitySection::key_of("Devil Powers"), "devil");
+    assert_eq!(AbilitySection::key_of("Physical Abilities"), "physical");
+    assert_eq!(AbilitySection::key_of(" Weaponry "), "weapons");
+    assert_eq!(
+        AbilitySection::key_of("Chainsaw Techniques"),
+        "chainsaw_techniques"
+    );
+    assert_eq!(AbilitySection::known_key("Chainsaw Techniques"), None);
+
+    let section = AbilitySection::new("Hybrid Transformation", Vec::new());
+    assert_eq!(section.key, "transformation");
+    assert_eq!(section.title, "Hybrid Transformation");
+}
+
+#[test]
+fn reads_abilities_keyed_by_kind() {
+    let detail: CharacterDetail = serde_json::from_str(
+        r#"{
+            "id": "aki-hayakawa",
+            "name": "Aki Hayakawa",
+            "alias_name": null,
+            "wiki_url": "https://chainsaw-man.fandom.com/wiki/Aki_Hayakawa",
+            "category": "Public Safety",
+            "names": {},
+            "image_src": null,
+            "images": [],
+            "gender": "Male",
+            "birthplace": null,
+            "status": "Deceased",
+            "occupations": [],
+            "affiliations": [],
+            "contracts": [],
+            "relatives": [],
+            "abilities": {
+                "supernatural": [{"name": "Fox Devil Contract", "description": "", "abilities": []}],
+                "physical": [{"name": "Swordsmanship", "description": "", "abilities": []}]
+            },
+            "revision_id": null,
+            "revision_timestamp": null
+        }"#,
+    )
+    .unwrap();
+
+    let keys = detail
+        .abilities
+        .iter()
+        .map(|section| section.key.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec!["physical", "supernatural"]);
+    assert_eq!(
+        detail.abilities_of("supernatural").unwrap()[0].name,
+        "Fox Devil Contract"
+    );
+}
+
+#[test]
+fn resolves_links_to_devils_before_characters() {
+    let mut targets = LinkTargets::default();
+    targets.insert("https://chainsaw-man.fandom.com/wiki/Power", "power");
+    targets.insert(
+        "https://chainsaw-man.fandom.com/wiki/Power",
+        "power-character",
+    );
+    targets.insert("https://chainsaw-man.fandom.com/wiki/Denji", "denji");
+
+    let link = |wiki_url: Option<&str>| EntityLink {
+        name: "name".to_string(),
+        wiki_url: wiki_url.map(str::to_string),
+        entity_id: Some("stale".to_string()),
+        qualifier: None,
+    };
+    let mut links = vec![
+        link(Some("https://chainsaw-man.fandom.com/wiki/Power")),
+        link(Some("https://chainsaw-man.fandom.com/wiki/Denji")),
+        link(Some("https://chainsaw-man.fandom.com/wiki/Makima")),
+        link(None),
+    ];
+    targets.resolve(&mut links);
+
+    assert_eq!(
+        links
+            .iter()
+            .map(|link| link.entity_id.as_deref())
+            .collect::<Vec<_>>(),
+        vec![Some("power"), Some("denji"), None, None]
+    );
+}
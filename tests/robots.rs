@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use chainsawman_api::scraper::RobotsTxt;
+
+const USER_AGENT: &str = "chainsawman-api/0.1.0 (+https://github.com/stevenhansel/chainsawman-api)";
+
+#[test]
+fn allows_everything_without_rules() {
+    let robots = RobotsTxt::parse("", USER_AGENT);
+
+    assert!(robots.is_allowed("/wiki/Bat_Devil"));
+    assert_eq!(robots.crawl_delay(), None);
+}
+
+#[test]
+fn applies_wildcard_group_rules() {
+    let robots = RobotsTxt::parse(
+        "# Fandom\nUser-agent: *\nDisallow: /wiki/Special:\nDisallow: /*?action=edit\n",
+        USER_AGENT,
+    );
+
+    assert!(robots.is_allowed("/wiki/Bat_Devil"));
+    assert!(!robots.is_allowed("/wiki/Special:Search"));
+    assert!(!robots.is_allowed("/wiki/Bat_Devil?action=edit"));
+}
+
+#[test]
+fn prefers_the_group_naming_the_user_agent() {
+    let robots = RobotsTxt::parse(
+        "User-agent: *\nDisallow: /\n\nUser-agent: Googlebot\nUser-agent: ChainsawMan-API\nDisallow: /wiki/Special:\nCrawl-delay: 2\n",
+        USER_AGENT,
+    );
+
+    assert!(robots.is_allowed("/wiki/Bat_Devil"));
+    assert!(!robots.is_allowed("/wiki/Special:Search"));
+    assert_eq!(robots.crawl_delay(), Some(Duration::from_secs(2)));
+}
+
+#[test]
+fn longest_matching_rule_wins() {
+    let robots = RobotsTxt::parse(
+        "User-agent: *\nDisallow: /wiki/\nAllow: /wiki/Bat\nDisallow: /wiki/Bat_Devil$\n",
+        USER_AGENT,
+    );
+
+    assert!(!robots.is_allowed("/wiki/Power"));
+    assert!(robots.is_allowed("/wiki/Bat_Fiend"));
+    assert!(!robots.is_allowed("/wiki/Bat_Devil"));
+    assert!(robots.is_allowed("/wiki/Bat_Devil/Gallery"));
+}
+
+#[test]
+fn allow_wins_a_tie() {
+    let robots = RobotsTxt::parse("User-agent: *\nDisallow: /wiki\nAllow: /wiki\n", USER_AGENT);
+
+    assert!(robots.is_allowed("/wiki/Power"));
+}
+
+#[test]
+fn disallow_all_refuses_every_path() {
+    let robots = RobotsTxt::disallow_all();
+
+    assert!(!robots.is_allowed("/"));
+    assert!(!robots.is_allowed("/wiki/Power"));
+}
@@ -0,0 +1,568 @@
+mod common;
+
+use std::sync::Arc;
+
+use chainsawman_api::{
+    models::{DevilCategory, Gender, ParseWarning, Status},
+    scraper::{
+        CharacterScraper, DevilScraper, DevilScraperConfig, Fetcher, FileFetcher, ScrapeError,
+        ScraperSelectors, SelectorsError, DEFAULT_SELECTORS, SELECTORS_SCHEMA_VERSION,
+    },
+    services::{CharacterDataSource, DevilDataSource},
+};
+
+use common::{find_devil, link};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+const BROKEN_FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/broken");
+const MISSING_FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/missing");
+
+fn scraper() -> DevilScraper {
+    scraper_with_fixtures(FIXTURES_DIR)
+}
+
+fn scraper_with_fixtures(dir: &str) -> DevilScraper {
+    DevilScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        Arc::new(FileFetcher::new(dir)),
+    )
+}
+
+#[tokio::test]
+async fn scrapes_devils_from_category_galleries() {
+    let mut devils = scraper().scrape_devils().await.unwrap();
+    devils.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let summary = devils
+        .iter()
+        .map(|devil| {
+            (
+                devil.id.as_str(),
+                devil.devil_name.as_str(),
+                devil.alias_name.as_deref(),
+                devil.category.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("bat-devil", "Bat Devil", None, "normal"),
+            ("darkness-devil", "Darkness Devil", None, "primal"),
+            ("denji", "Chainsaw Man", Some("Denji"), "hybrid"),
+            ("leech-devil", "Leech Devil", None, "normal"),
+            ("nayuta", "Control Devil", Some("Nayuta"), "reincarnated"),
+            ("power", "Power", None, "fiend"),
+        ]
+    );
+    assert_eq!(
+        devils[0].wiki_url,
+        "https://chainsaw-man.fandom.com/wiki/Bat_Devil"
+    );
+}
+
+#[tokio::test]
+async fn scrapes_devil_detail_infobox() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "bat-devil").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(detail.id, "bat-devil");
+    assert_eq!(detail.devil_name, "Bat Devil");
+    assert_eq!(detail.category, DevilCategory::Normal);
+    assert_eq!(
+        detail.wiki_url,
+        "https://chainsaw-man.fandom.com/wiki/Bat_Devil"
+    );
+    assert_eq!(detail.names["kanji"].devil_name, "コウモリの悪魔");
+    assert_eq!(detail.names["romaji"].devil_name, "Kōmori no Akuma");
+    assert_eq!(detail.names["romaji"].alias_name, None);
+    assert_eq!(detail.gender, Some(Gender::Male));
+    assert_eq!(detail.birthplace.as_deref(), Some("Hell"));
+    assert_eq!(detail.status, Some(Status::Deceased));
+    assert_eq!(
+        detail.occupations,
+        vec!["Hunting humans", "Terrorizing the city"]
+    );
+    assert_eq!(detail.affiliations, vec!["Leech Devil", "Bats"]);
+    assert_eq!(detail.contracts, vec![link("Rio", Some("Rio"), None)]);
+    assert_eq!(
+        detail.relatives,
+        vec![
+            link("Leech Devil", Some("Leech_Devil"), Some("partner")),
+            link("Bat Fiend", None, None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn scrapes_devil_detail_abilities() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "bat-devil").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let physical = detail
+        .abilities_of("physical")
+        .unwrap()
+        .iter()
+        .map(|ability| ability.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(physical, vec!["Enhanced Strength", "Flight"]);
+    assert_eq!(
+        detail.abilities_of("physical").unwrap()[0].description,
+        "The Bat Devil is strong enough to crush a building."
+    );
+    assert_eq!(
+        detail.abilities_of("physical").unwrap()[1].description,
+        "The Bat Devil can fly using its wings."
+    );
+
+    let devil_powers = detail.abilities_of("devil").unwrap();
+    assert_eq!(devil_powers.len(), 2);
+    assert_eq!(devil_powers[0].name, "Regeneration");
+    assert_eq!(
+        devil_powers[0].description,
+        "The Bat Devil heals by drinking human blood."
+    );
+    assert_eq!(devil_powers[1].name, "Sonic Scream");
+
+    let regeneration = devil_powers[0]
+        .abilities
+        .iter()
+        .map(|ability| ability.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(regeneration, vec!["Blood Absorption", "Limb Regrowth"]);
+
+    assert!(detail.abilities_of("supernatural").is_none());
+}
+
+#[tokio::test]
+async fn scrapes_romaji_alias_name() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "denji").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(detail.devil_name, "Chainsaw Man");
+    assert_eq!(detail.alias_name.as_deref(), Some("Denji"));
+    assert_eq!(detail.category, DevilCategory::Hybrid);
+    assert_eq!(detail.names["romaji"].devil_name, "Denji");
+    assert_eq!(
+        detail.names["romaji"].alias_name.as_deref(),
+        Some("Chensō Man")
+    );
+}
+
+#[tokio::test]
+async fn scrapes_every_heading_of_the_abilities_section() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "denji").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let sections = detail
+        .abilities
+        .iter()
+        .map(|section| (section.key.as_str(), section.title.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        vec![
+            ("transformation", "Hybrid Transformation"),
+            ("chainsaw_techniques", "Chainsaw Techniques"),
+            ("physical", "Physical Abilities"),
+        ]
+    );
+    assert_eq!(
+        detail.abilities_of("chainsaw_techniques").unwrap()[0].name,
+        "Chainsaw Whip"
+    );
+}
+
+#[tokio::test]
+async fn scrapes_kanji_names_without_their_furigana() {
+    let scraper = scraper();
+
+    let devil = find_devil(&scraper, "bat-devil").await;
+    let kanji = &scraper.scrape_devil_detail(&devil).await.unwrap().names["kanji"];
+    assert_eq!(kanji.devil_name, "コウモリの悪魔");
+    assert_eq!(kanji.reading.as_deref(), Some("コウモリのあくま"));
+    assert_eq!(kanji.alias_name, None);
+    assert_eq!(kanji.alias_reading, None);
+
+    let devil = find_devil(&scraper, "nayuta").await;
+    let kanji = &scraper.scrape_devil_detail(&devil).await.unwrap().names["kanji"];
+    assert_eq!(kanji.devil_name, "ナユタ");
+    assert_eq!(kanji.reading, None);
+    assert_eq!(kanji.alias_name.as_deref(), Some("支配の悪魔"));
+    assert_eq!(kanji.alias_reading.as_deref(), Some("しはいのあくま"));
+
+    let devil = find_devil(&scraper, "darkness-devil").await;
+    let kanji = &scraper.scrape_devil_detail(&devil).await.unwrap().names["kanji"];
+    assert_eq!(kanji.devil_name, "闇の悪魔");
+    assert_eq!(kanji.reading, None);
+    assert_eq!(kanji.alias_name, None);
+}
+
+#[tokio::test]
+async fn scrapes_infobox_image() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "bat-devil").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let src = "https://static.wikia.nocookie.net/chainsaw-man/images/b/b1/Bat_Devil.png/revision/latest?cb=20201026184104";
+    assert_eq!(detail.image_src.as_deref(), Some(src));
+    assert_eq!(detail.images.len(), 1);
+    assert_eq!(detail.images[0].src, src);
+    assert_eq!(detail.images[0].label, None);
+    assert_eq!(
+        detail.images[0].caption.as_deref(),
+        Some("The Bat Devil in the manga")
+    );
+}
+
+#[tokio::test]
+async fn scrapes_tabbed_infobox_images() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "denji").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    let images = detail
+        .images
+        .iter()
+        .map(|image| (image.label.as_deref(), image.caption.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        images,
+        vec![
+            (Some("Manga"), Some("Denji in the manga")),
+            (Some("Anime"), None)
+        ]
+    );
+    assert_eq!(detail.image_src, Some(detail.images[0].src.clone()));
+    assert!(detail.images[1].src.contains("Denji_Anime.png"));
+}
+
+#[tokio::test]
+async fn scrapes_devil_without_image() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "power").await;
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(detail.image_src, None);
+    assert!(detail.images.is_empty());
+}
+
+#[tokio::test]
+async fn scrapes_every_devil_detail() {
+    let scraper = scraper();
+    let devils = scraper.scrape_devils().await.unwrap();
+
+    let result = scraper.scrape(&devils).await.unwrap();
+
+    let mut ids = result
+        .details
+        .iter()
+        .map(|detail| detail.id.as_str())
+        .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(
+        ids,
+        vec!["bat-devil", "darkness-devil", "denji", "nayuta", "power"]
+    );
+
+    let darkness = result
+        .details
+        .iter()
+        .find(|detail| detail.id == "darkness-devil")
+        .unwrap();
+    assert_eq!(
+        darkness.abilities_of("supernatural").unwrap()[0].name,
+        "Darkness Manipulation"
+    );
+}
+
+#[tokio::test]
+async fn reports_failed_devils_without_dropping_the_scrape() {
+    let scraper = scraper();
+    let devils = scraper.scrape_devils().await.unwrap();
+
+    let report = scraper.scrape(&devils).await.unwrap().report;
+
+    assert_eq!(report.succeeded.len(), 5);
+    assert_eq!(report.failed.len(), 1);
+    assert!(report.started_at <= report.finished_at);
+
+    let failed = &report.failed[0];
+    assert_eq!(failed.id, "leech-devil");
+    assert_eq!(
+        failed.wiki_url,
+        "https://chainsaw-man.fandom.com/wiki/Leech_Devil"
+    );
+    assert_eq!(failed.category, "normal");
+    assert_eq!(failed.error_kind, "io");
+}
+
+fn character_scraper() -> CharacterScraper {
+    CharacterScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            ..DevilScraperConfig::default()
+        },
+        Arc::new(FileFetcher::new(FIXTURES_DIR)),
+    )
+}
+
+#[tokio::test]
+async fn scrapes_characters_from_category_galleries() {
+    let characters = character_scraper().scrape_characters().await.unwrap();
+
+    let summary = characters
+        .iter()
+        .map(|character| {
+            (
+                character.id.as_str(),
+                character.name.as_str(),
+                character.category.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("aki-hayakawa", "Aki Hayakawa", "Public Safety"),
+            ("kishibe", "Kishibe", "Devil Hunters"),
+            ("rio", "Rio", "Humans"),
+        ]
+    );
+    assert_eq!(
+        characters[0].wiki_url,
+        "https://chainsaw-man.fandom.com/wiki/Aki_Hayakawa"
+    );
+}
+
+#[tokio::test]
+async fn scrapes_character_detail_with_the_devil_infobox_parsing() {
+    let scraper = character_scraper();
+    let characters = scraper.scrape_characters().await.unwrap();
+
+    let detail = scraper
+        .scrape_character_detail(&characters[0])
+        .await
+        .unwrap();
+
+    assert_eq!(detail.id, "aki-hayakawa");
+    assert_eq!(detail.name, "Aki Hayakawa");
+    assert_eq!(detail.names["kanji"].devil_name, "早川 アキ");
+    assert_eq!(detail.names["romaji"].devil_name, "Hayakawa Aki");
+    assert_eq!(detail.gender, Some(Gender::Male));
+    assert_eq!(detail.birthplace.as_deref(), Some("Japan"));
+    assert_eq!(detail.status, Some(Status::Deceased));
+    assert_eq!(detail.occupations, vec!["Devil Hunter"]);
+    assert_eq!(detail.affiliations, vec!["Special Division 4"]);
+    assert_eq!(
+        detail.relatives,
+        vec![link("Taiyo Hayakawa", None, Some("brother"))]
+    );
+    assert!(detail.images[0].src.contains("Aki_Hayakawa.png"));
+
+    let supernatural = detail
+        .abilities_of("supernatural")
+        .unwrap()
+        .iter()
+        .map(|ability| ability.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        supernatural,
+        vec!["Fox Devil Contract", "Curse Devil Contract"]
+    );
+}
+
+#[tokio::test]
+async fn reports_failed_characters_without_dropping_the_scrape() {
+    let scraper = character_scraper();
+    let characters = scraper.scrape_characters().await.unwrap();
+
+    let result = scraper.scrape_character_details(&characters).await.unwrap();
+
+    assert_eq!(result.details.len(), 2);
+    assert_eq!(result.report.failed.len(), 1);
+    assert_eq!(result.report.failed[0].id, "rio");
+    assert!(result.report.failed[0]
+        .error
+        .starts_with("Unable to scrape character"));
+}
+
+#[tokio::test]
+async fn file_fetcher_serves_saved_pages_only() {
+    let fetcher = FileFetcher::new(FIXTURES_DIR);
+
+    let page = fetcher
+        .fetch("https://chainsaw-man.fandom.com/wiki/Power")
+        .await
+        .unwrap();
+    assert!(page.contains("<b>Power</b> is the Blood Fiend."));
+
+    assert!(fetcher
+        .fetch("https://chainsaw-man.fandom.com/wiki/Leech_Devil")
+        .await
+        .is_err());
+    assert!(fetcher
+        .fetch("https://chainsaw-man.fandom.com/wiki/../Cargo")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn reports_missing_category_gallery() {
+    let error = scraper_with_fixtures(BROKEN_FIXTURES_DIR)
+        .scrape_devils()
+        .await
+        .unwrap_err();
+
+    match error {
+        ScrapeError::MissingSection { url, section } => {
+            assert_eq!(url, "https://chainsaw-man.fandom.com/wiki/devil");
+            assert_eq!(section, "gallery-5");
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+}
+
+#[tokio::test]
+async fn scrapes_galleries_moved_in_the_selectors() {
+    // The broken devil page has no Hybrids gallery, mapping it to another one is a config edit
+    let selectors = DEFAULT_SELECTORS.replace(
+        "category = \"Hybrids\"\nid = \"gallery-5\"",
+        "category = \"Hybrids\"\nid = \"gallery-4\"",
+    );
+    let scraper = DevilScraper::new(
+        DevilScraperConfig {
+            num_of_workers: 2,
+            selectors: Arc::new(ScraperSelectors::from_toml(&selectors).unwrap()),
+        },
+        Arc::new(FileFetcher::new(BROKEN_FIXTURES_DIR)),
+    );
+
+    let devils = scraper.scrape_devils().await.unwrap();
+
+    assert!(devils
+        .iter()
+        .any(|devil| devil.category == DevilCategory::Hybrid));
+}
+
+#[test]
+fn rejects_selectors_of_another_version() {
+    let selectors = DEFAULT_SELECTORS.replace(
+        &format!("version = {}", SELECTORS_SCHEMA_VERSION),
+        "version = 99",
+    );
+    assert!(matches!(
+        ScraperSelectors::from_toml(&selectors),
+        Err(SelectorsError::UnsupportedVersion(99))
+    ));
+
+    let selectors =
+        DEFAULT_SELECTORS.replace(&format!("version = {}", SELECTORS_SCHEMA_VERSION), "");
+    assert!(matches!(
+        ScraperSelectors::from_toml(&selectors),
+        Err(SelectorsError::Unversioned)
+    ));
+}
+
+#[test]
+fn rejects_invalid_selectors() {
+    let selectors =
+        DEFAULT_SELECTORS.replace("headline = \"span.mw-headline\"", "headline = \"span[\"");
+    match ScraperSelectors::from_toml(&selectors) {
+        Err(SelectorsError::InvalidSelector { key, selector }) => {
+            assert_eq!(key, "abilities.headline");
+            assert_eq!(selector, "span[");
+        }
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+
+    let selectors = DEFAULT_SELECTORS.replace("name_section = \"Name\"", "name_section = \"\"");
+    assert!(matches!(
+        ScraperSelectors::from_toml(&selectors),
+        Err(SelectorsError::Empty(key)) if key == "infobox.name_section"
+    ));
+
+    let selectors = DEFAULT_SELECTORS.replace("[abilities]", "[abilities]\nheading = \"h2\"");
+    assert!(matches!(
+        ScraperSelectors::from_toml(&selectors),
+        Err(SelectorsError::Toml(_))
+    ));
+}
+
+#[tokio::test]
+async fn warns_about_unparseable_romaji_name() {
+    let devil = find_devil(&scraper(), "power").await;
+    let scraper = scraper_with_fixtures(BROKEN_FIXTURES_DIR);
+
+    let detail = scraper.scrape_devil_detail(&devil).await.unwrap();
+
+    assert_eq!(detail.names["romaji"].devil_name, "Pawā");
+    // The page has no birthplace and no Professional Information section either
+    assert_eq!(
+        detail
+            .warnings
+            .iter()
+            .map(|warning| warning.field.as_str())
+            .collect::<Vec<&str>>(),
+        vec![
+            "romaji",
+            "birthplace",
+            "occupation",
+            "affiliation",
+            "contracted humans",
+            "relatives"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn warns_about_missing_infobox_fields() {
+    let devil = find_devil(&scraper(), "power").await;
+
+    let detail = scraper_with_fixtures(MISSING_FIXTURES_DIR)
+        .scrape_devil_detail(&devil)
+        .await
+        .unwrap();
+
+    assert_eq!(detail.gender, None);
+    assert_eq!(detail.status, Some(Status::Deceased));
+    assert!(detail
+        .warnings
+        .contains(&ParseWarning::new("gender", "the field is missing")));
+
+    let detail = scraper().scrape_devil_detail(&devil).await.unwrap();
+    assert!(!detail
+        .warnings
+        .iter()
+        .any(|warning| warning.field == "gender"));
+}
+
+#[tokio::test]
+async fn reports_missing_page_with_its_url() {
+    let scraper = scraper();
+    let devil = find_devil(&scraper, "leech-devil").await;
+
+    let error = scraper.scrape_devil_detail(&devil).await.unwrap_err();
+
+    assert!(matches!(error, ScrapeError::Io { .. }));
+    assert_eq!(
+        error.url(),
+        "https://chainsaw-man.fandom.com/wiki/Leech_Devil"
+    );
+}